@@ -0,0 +1,108 @@
+//! Compares the ElfCode interpreter with the compiled closures on generated programs.
+//!
+//! Run with `cargo bench --bench elfcode`.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use advent_of_code::elfcode::{Instruction, Machine, Opcode, Operand};
+
+/// The instruction pointer is bound to r5, and r4 holds the loop conditions.
+const IP: usize = 5;
+
+/// A small xorshift generator, so that every run benchmarks the same programs.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self, bound: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % bound as u64) as usize
+    }
+}
+
+/// Two nested loops of `outer` and `inner` iterations around `body` random instructions
+/// that only write r2 and r3.
+fn generate(body: usize, outer: usize, inner: usize, rng: &mut Rng) -> Vec<Instruction> {
+    use Opcode::*;
+    // no `addr`, which could double a register on every iteration until it overflows
+    const FILLER: [Opcode; 9] = [addi, banr, bani, borr, bori, setr, gtrr, eqri, eqrr];
+
+    let mut program = vec![
+        Instruction::new(seti, 0, 0, 0),
+        Instruction::new(seti, 0, 0, 1),
+    ];
+    for _ in 0..body {
+        let opcode = FILLER[rng.next(FILLER.len())];
+        let input2 = match opcode.operands()[1] {
+            Operand::Register => rng.next(4),
+            _ => rng.next(1024),
+        };
+        let output = 2 + rng.next(2);
+        program.push(Instruction::new(opcode, rng.next(4), input2, output));
+    }
+
+    program.extend([
+        Instruction::new(addi, 1, 1, 1),
+        Instruction::new(gtri, 1, inner, 4),
+        Instruction::new(addr, 4, IP, IP),
+        Instruction::new(seti, 1, 0, IP),
+        Instruction::new(addi, 0, 1, 0),
+        Instruction::new(gtri, 0, outer, 4),
+        Instruction::new(addr, 4, IP, IP),
+        Instruction::new(seti, 0, 0, IP),
+    ]);
+    program
+}
+
+/// The fastest of a few runs, which is the least disturbed by everything else on the machine.
+fn time(runs: usize, mut f: impl FnMut() -> Machine<6>) -> (Duration, Machine<6>) {
+    (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            let machine = black_box(f());
+            (start.elapsed(), machine)
+        })
+        .min_by_key(|(elapsed, _)| *elapsed)
+        .unwrap()
+}
+
+fn main() {
+    let mut rng = Rng(0x5eed);
+
+    println!(
+        "{:<24}{:>14}{:>14}{:>14}{:>10}",
+        "program", "instructions", "interpreter", "closures", "speedup"
+    );
+
+    for (body, outer, inner) in [(4, 100, 1000), (16, 100, 1000), (64, 20, 1000)] {
+        let program = generate(body, outer, inner, &mut rng);
+        let machine = Machine::<6>::new(Some(IP), program);
+
+        let (interpreted, expected) = time(5, || {
+            let mut machine = machine.clone();
+            machine.run();
+            machine
+        });
+
+        let compiled = machine.compile();
+        let (closures, actual) = time(5, || {
+            let mut machine = machine.clone();
+            compiled.run(&mut machine);
+            machine
+        });
+
+        assert_eq!(actual.registers, expected.registers);
+        println!(
+            "{:<24}{:>14}{:>14}{:>14}{:>9.1}x",
+            format!("{body} in {outer} x {inner} loops"),
+            expected.executed(),
+            format!("{interpreted:.2?}"),
+            format!("{closures:.2?}"),
+            interpreted.as_secs_f64() / closures.as_secs_f64()
+        );
+    }
+}
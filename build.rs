@@ -0,0 +1,123 @@
+//! Generates the registry of solutions that the `serve` command runs in-process.
+//!
+//! Every `src/bin/NN.rs` and `src/bin/YYYY-NN.rs` is included as a module of the main binary,
+//! and each of its `part_one` / `part_two` functions becomes an entry in `SOLUTIONS`.
+//!
+//! It also compiles the ElfCode programs listed in `ELFCODE_NATIVE` to Rust functions.
+use std::{env, fs, path::Path};
+
+// these modules are part of `elfcode` as well. Since the build script includes them, they may
+// only use `std` and the build dependencies, which is why `emit` has its own line parser.
+#[path = "src/elfcode/emit.rs"]
+mod emit;
+#[path = "src/elfcode/fixtures.rs"]
+mod fixtures;
+#[path = "src/elfcode/opcode.rs"]
+mod opcode;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/bin");
+    println!("cargo:rerun-if-changed=src/elfcode/emit.rs");
+    println!("cargo:rerun-if-changed=src/elfcode/fixtures.rs");
+    println!("cargo:rerun-if-changed=src/elfcode/opcode.rs");
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    solutions(&out_dir);
+    elfcode(&out_dir);
+}
+
+fn solutions(out_dir: &str) {
+    let out_path = Path::new(out_dir).join("solutions.rs");
+
+    // only the `serve` feature compiles solutions into the main binary.
+    if env::var("CARGO_FEATURE_SERVE").is_err() {
+        fs::write(out_path, "").unwrap();
+        return;
+    }
+
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let bin_dir = Path::new(&manifest_dir).join("src").join("bin");
+
+    let mut bins: Vec<String> = fs::read_dir(&bin_dir)
+        .unwrap()
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            let name = name.strip_suffix(".rs")?.to_string();
+            let is_solution = match name.len() {
+                2 => name.chars().all(|c| c.is_ascii_digit()),
+                7 => {
+                    name.chars()
+                        .enumerate()
+                        .all(|(i, c)| if i == 4 { c == '-' } else { c.is_ascii_digit() })
+                }
+                _ => false,
+            };
+            is_solution.then_some(name)
+        })
+        .collect();
+    bins.sort_unstable();
+
+    let mut modules = String::new();
+    let mut entries = String::new();
+
+    for bin in bins {
+        let path = bin_dir.join(format!("{bin}.rs"));
+        let source = fs::read_to_string(&path).unwrap();
+        let module = format!("solution_{}", bin.replace('-', "_"));
+
+        modules.push_str(&format!(
+            "#[allow(dead_code, unused_imports, clippy::all)]\n#[path = {:?}]\nmod {module};\n",
+            path.display().to_string()
+        ));
+
+        for (part, func) in [(1, "part_one"), (2, "part_two")] {
+            if source.contains(&format!("pub fn {func}(")) {
+                entries.push_str(&format!(
+                    "    Solution {{ bin: {bin:?}, part: {part}, func: |input| {module}::{func}(input).map(Into::into) }},\n"
+                ));
+            }
+        }
+    }
+
+    let generated = format!("{modules}\npub static SOLUTIONS: &[Solution] = &[\n{entries}];\n");
+    fs::write(out_path, generated).unwrap();
+}
+
+/// `ELFCODE_NATIVE` is a comma-separated list of `name=path` pairs. Each program becomes a
+/// function `name` in `advent_of_code::elfcode::native`.
+fn elfcode(out_dir: &str) {
+    println!("cargo:rerun-if-env-changed=ELFCODE_NATIVE");
+    let out_path = Path::new(out_dir).join("elfcode.rs");
+
+    let programs = env::var("ELFCODE_NATIVE").unwrap_or_default();
+    let mut generated = String::new();
+
+    // the tests compare what `emit` makes of the test programs with the machine
+    if env::var("CARGO_FEATURE_TEST_LIB").is_ok() {
+        for (name, source) in [
+            ("fixture_count", fixtures::COUNT),
+            ("fixture_divisor_sum", fixtures::DIVISOR_SUM),
+            ("fixture_divide", fixtures::DIVIDE),
+            ("fixture_overflow", fixtures::OVERFLOW),
+        ] {
+            generated.push_str(&emit::emit(name, source).unwrap());
+        }
+    }
+
+    for entry in programs.split(',').filter(|entry| !entry.trim().is_empty()) {
+        let Some((name, path)) = entry.trim().split_once('=') else {
+            panic!("ELFCODE_NATIVE: expected name=path, found \"{entry}\"");
+        };
+        println!("cargo:rerun-if-changed={path}");
+
+        let source = fs::read_to_string(path)
+            .unwrap_or_else(|e| panic!("ELFCODE_NATIVE: could not read {path}: {e}"));
+        match emit::emit(name, &source) {
+            Ok(function) => generated.push_str(&function),
+            Err(e) => panic!("ELFCODE_NATIVE: {path}: {e}"),
+        }
+    }
+
+    fs::write(out_path, generated).unwrap();
+}
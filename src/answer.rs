@@ -0,0 +1,203 @@
+use std::{collections::HashMap, fmt};
+
+use tinyjson::JsonValue;
+
+/// The answer to a puzzle part.
+///
+/// Solutions can return any type that converts into an `Answer`: integers, strings,
+/// coordinate tuples such as `(x, y)` or `(x, y, size)`, or an `Answer` directly.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    Int(i128),
+    Str(String),
+    /// A coordinate tuple, formatted as `x,y` like the puzzles expect it.
+    Coord(Vec<i128>),
+    /// A picture that has to be read by a human, e.g. letters drawn with `#`.
+    Grid(Vec<String>),
+    None,
+}
+
+impl Answer {
+    /// Build a grid answer from the rows of a picture.
+    pub fn grid<S: ToString>(rows: impl IntoIterator<Item = S>) -> Self {
+        Answer::Grid(rows.into_iter().map(|row| row.to_string()).collect())
+    }
+
+    /// Returns true for answers that span multiple lines when printed.
+    pub fn is_multi_line(&self) -> bool {
+        matches!(self, Answer::Grid(_))
+    }
+
+    /// Returns the string to submit, or `None` if the answer can not be submitted as is.
+    ///
+    /// Grids have to be read, and the letters they show submitted by hand.
+    pub fn submit_string(&self) -> Option<String> {
+        match self {
+            Answer::Grid(_) | Answer::None => None,
+            answer => Some(answer.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{n}"),
+            Answer::Str(s) => write!(f, "{s}"),
+            Answer::Coord(values) => {
+                let values: Vec<String> = values.iter().map(i128::to_string).collect();
+                write!(f, "{}", values.join(","))
+            }
+            Answer::Grid(rows) => write!(f, "{}", rows.join("\n")),
+            Answer::None => write!(f, "✖"),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+macro_rules! impl_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Answer::Int(value as i128)
+                }
+            }
+
+            impl From<($t, $t)> for Answer {
+                fn from((x, y): ($t, $t)) -> Self {
+                    Answer::Coord(vec![x as i128, y as i128])
+                }
+            }
+
+            impl From<($t, $t, $t)> for Answer {
+                fn from((x, y, z): ($t, $t, $t)) -> Self {
+                    Answer::Coord(vec![x as i128, y as i128, z as i128])
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl From<String> for Answer {
+    /// Multi-line strings become grids.
+    fn from(value: String) -> Self {
+        if value.contains('\n') {
+            Answer::grid(value.lines())
+        } else {
+            Answer::Str(value)
+        }
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::from(value.to_string())
+    }
+}
+
+impl From<char> for Answer {
+    fn from(value: char) -> Self {
+        Answer::Str(value.to_string())
+    }
+}
+
+impl<T: Into<Answer>> From<Option<T>> for Answer {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Answer::None, Into::into)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The form `cargo serve` responds with. Stored answers, timings and reports keep the
+/// [submit string](Answer::submit_string) instead, because they compare answers as text.
+impl From<&Answer> for JsonValue {
+    fn from(value: &Answer) -> Self {
+        // numbers beyond the precision of a JSON number are written as strings.
+        let number = |n: i128| {
+            if n.unsigned_abs() <= 1 << 53 {
+                JsonValue::Number(n as f64)
+            } else {
+                JsonValue::String(n.to_string())
+            }
+        };
+
+        let (kind, json) = match value {
+            Answer::Int(n) => ("int", number(*n)),
+            Answer::Str(s) => ("string", JsonValue::String(s.clone())),
+            Answer::Coord(values) => (
+                "coordinate",
+                JsonValue::Array(values.iter().map(|n| number(*n)).collect()),
+            ),
+            Answer::Grid(rows) => (
+                "grid",
+                JsonValue::Array(rows.iter().cloned().map(JsonValue::String).collect()),
+            ),
+            Answer::None => ("none", JsonValue::Null),
+        };
+
+        let submit = value
+            .submit_string()
+            .map_or(JsonValue::Null, JsonValue::String);
+
+        JsonValue::Object(HashMap::from([
+            ("type".to_string(), JsonValue::String(kind.into())),
+            ("value".to_string(), json),
+            ("submit".to_string(), submit),
+        ]))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use tinyjson::JsonValue;
+
+    use super::Answer;
+
+    #[test]
+    fn converts_solution_results() {
+        assert_eq!(Answer::from(42_u32), Answer::Int(42));
+        assert_eq!(Answer::from(-3_i32), Answer::Int(-3));
+        assert_eq!(Answer::from("abc"), Answer::Str("abc".into()));
+        assert_eq!(Answer::from((33_u32, 45_u32)), Answer::Coord(vec![33, 45]));
+        assert_eq!(
+            Answer::from(".#\n#.".to_string()),
+            Answer::grid([".#", "#."])
+        );
+        assert_eq!(Answer::from(None::<u32>), Answer::None);
+    }
+
+    #[test]
+    fn formats_answers() {
+        assert_eq!(Answer::Int(42).to_string(), "42");
+        assert_eq!(Answer::Coord(vec![90, 269, 16]).to_string(), "90,269,16");
+        assert_eq!(Answer::grid([".#", "#."]).to_string(), ".#\n#.");
+        assert_eq!(
+            Answer::Coord(vec![90, 269, 16]).submit_string(),
+            Some("90,269,16".into())
+        );
+        assert_eq!(Answer::grid([".#"]).submit_string(), None);
+        assert_eq!(Answer::None.submit_string(), None);
+    }
+
+    #[test]
+    fn serializes_answers() {
+        let json = JsonValue::from(&Answer::Coord(vec![7, 3]));
+        assert_eq!(
+            json.stringify().unwrap().len(),
+            r#"{"type":"coordinate","value":[7,3],"submit":"7,3"}"#.len()
+        );
+        assert_eq!(json["type"], JsonValue::String("coordinate".into()));
+        assert_eq!(json["submit"], JsonValue::String("7,3".into()));
+
+        let json = JsonValue::from(&Answer::Int(1 << 60));
+        assert_eq!(json["value"], JsonValue::String((1_i128 << 60).to_string()));
+        assert_eq!(JsonValue::from(&Answer::None)["submit"], JsonValue::Null);
+    }
+}
@@ -2,23 +2,20 @@ advent_of_code::solution!(1);
 
 use std::{cell::Cell, collections::HashSet};
 
-use nom::{
-    character::complete::{i32, newline},
-    multi::separated_list0,
-    IResult,
-};
+use advent_of_code::{lines, parse_all, signed};
+use nom::IResult;
 
 fn parser(i: &str) -> IResult<&str, Vec<i32>> {
-    separated_list0(newline, i32)(i)
+    lines(signed())(i)
 }
 
 pub fn part_one(input: &str) -> Option<i32> {
-    let (_, nums) = parser(input).unwrap();
+    let nums = parse_all(parser, input).unwrap();
     Some(nums.iter().sum())
 }
 
 pub fn part_two(input: &str) -> Option<i32> {
-    let (_, nums) = parser(input).unwrap();
+    let nums = parse_all(parser, input).unwrap();
     let mut set = HashSet::new();
     // initial value
     set.insert(0);
@@ -1,3 +1,4 @@
+use advent_of_code::parse_all;
 use itertools::Itertools;
 use levenshtein::levenshtein;
 use nom::{
@@ -13,7 +14,7 @@ fn parser(i: &str) -> IResult<&str, Vec<&str>> {
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
-    let (_, boxes) = parser(input).unwrap();
+    let boxes = parse_all(parser, input).unwrap();
     let two = boxes
         .iter()
         .map(|v| v.chars().counts().values().any(|&x| x == 2))
@@ -28,7 +29,7 @@ pub fn part_one(input: &str) -> Option<usize> {
 }
 
 pub fn part_two(input: &str) -> Option<String> {
-    let (_, boxes) = parser(input).unwrap();
+    let boxes = parse_all(parser, input).unwrap();
     let closest_two = boxes
         .iter()
         .combinations(2)
@@ -2,7 +2,7 @@ advent_of_code::solution!(3);
 
 use std::collections::{HashMap, HashSet};
 
-use advent_of_code::{coord_parse, number, Coordinate};
+use advent_of_code::{coord_parse, number, parse_all, Coordinate};
 use itertools::Itertools;
 use nom::{
     bytes::complete::tag,
@@ -45,7 +45,7 @@ fn parser(i: &str) -> IResult<&str, Vec<Claim>> {
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
-    let (_, claims) = parser(input).unwrap();
+    let claims = parse_all(parser, input).unwrap();
     let mut fabric = HashMap::new();
 
     for claim in claims.iter() {
@@ -60,7 +60,7 @@ pub fn part_one(input: &str) -> Option<usize> {
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let (_, claims) = parser(input).unwrap();
+    let claims = parse_all(parser, input).unwrap();
     let mut fabric: HashMap<(u32, u32), (u32, u32)> = HashMap::new();
     let mut clean_ids = HashSet::new();
 
@@ -4,7 +4,7 @@ use std::{collections::HashMap, iter, ops::Range};
 
 use chrono::{NaiveDateTime, Timelike};
 
-use advent_of_code::number;
+use advent_of_code::{number, parse_all};
 
 use nom::{
     branch::alt,
@@ -91,7 +91,8 @@ fn timing(i: &Vec<Observation>) -> HashMap<u32, HashMap<u32, u32>> {
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let (_, mut observations) = parser(input).unwrap();
+    let mut observations = parse_all(parser, input).unwrap();
+    #[allow(clippy::unnecessary_sort_by)]
     observations.sort_unstable_by(|a, b| a.timestamp.cmp(&b.timestamp));
     let shifts = timing(&observations);
 
@@ -108,7 +109,8 @@ pub fn part_one(input: &str) -> Option<u32> {
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let (_, mut observations) = parser(input).unwrap();
+    let mut observations = parse_all(parser, input).unwrap();
+    #[allow(clippy::unnecessary_sort_by)]
     observations.sort_unstable_by(|a, b| a.timestamp.cmp(&b.timestamp));
     let shifts = timing(&observations);
 
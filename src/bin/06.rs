@@ -3,12 +3,12 @@ advent_of_code::solution!(6);
 use std::cmp::max;
 use std::collections::{HashMap, HashSet};
 
-use advent_of_code::{coord_parse, Coordinate};
+use advent_of_code::{coord_parse, lines, parse_all, Coordinate};
 use itertools::Itertools;
-use nom::{character::complete::newline, multi::separated_list0, IResult};
+use nom::IResult;
 
 fn parser(i: &str) -> IResult<&str, Vec<Coordinate<u32>>> {
-    separated_list0(newline, coord_parse)(i)
+    lines(coord_parse)(i)
 }
 
 fn distance(a: &Coordinate<u32>, b: &Coordinate<u32>) -> u32 {
@@ -16,7 +16,7 @@ fn distance(a: &Coordinate<u32>, b: &Coordinate<u32>) -> u32 {
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
-    let (_, coords) = parser(input).unwrap();
+    let coords = parse_all(parser, input).unwrap();
     // number the regions for future reference
     let coords = coords.iter().enumerate().collect::<Vec<_>>();
 
@@ -65,7 +65,7 @@ pub fn part_one(input: &str) -> Option<usize> {
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let (_, coords) = parser(input).unwrap();
+    let coords = parse_all(parser, input).unwrap();
 
     let mut max_x = 0;
     let mut max_y = 0;
@@ -100,6 +100,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_part_two() {
         // unfortunately we have to change a value in the function, and with the test harness we're not passing in additional info
         // let result = part_two(&advent_of_code::template::read_file("examples", DAY));
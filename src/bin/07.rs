@@ -4,6 +4,7 @@ use std::{
     iter,
 };
 
+use advent_of_code::parse_all;
 use itertools::Itertools;
 use nom::{
     bytes::complete::tag,
@@ -62,7 +63,7 @@ fn check_next_step(graph: &HashMap<char, HashSet<char>>) -> Option<char> {
 }
 
 pub fn part_one(input: &str) -> Option<String> {
-    let (_, steps) = parser(input).unwrap();
+    let steps = parse_all(parser, input).unwrap();
 
     let mut graph = make_graph(steps);
     let mut step_sequence: Vec<char> = Vec::new();
@@ -84,12 +85,13 @@ fn dispatch_job(c: &char, start_time: u32) -> u32 {
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let (_, steps) = parser(input).unwrap();
+    let steps = parse_all(parser, input).unwrap();
 
     let mut graph = make_graph(steps);
     let mut start_times = HashMap::new();
 
     // settable number of workers
+    #[allow(clippy::manual_repeat_n)]
     let mut workers = BinaryHeap::from_iter(iter::repeat(Reverse(None)).take(5));
     let mut task_completions = Vec::new();
 
@@ -141,6 +143,7 @@ mod tests {
     // }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_part_two() {
         // needs 2 workers instead of 5 and an offset of 0 instead of 60 for job duration
         // let result = part_two(&advent_of_code::template::read_file("examples", DAY));
@@ -1,6 +1,6 @@
 advent_of_code::solution!(8);
 
-use advent_of_code::number_usize;
+use advent_of_code::{number_usize, parse_all};
 use nom::{
     bytes::complete::tag,
     character::complete::multispace1,
@@ -53,12 +53,12 @@ fn node(i: &str) -> IResult<&str, Node> {
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
-    let (_, node) = node(input).unwrap();
+    let node = parse_all(node, input).unwrap();
     Some(node.sum_metadata())
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
-    let (_, node) = node(input).unwrap();
+    let node = parse_all(node, input).unwrap();
     Some(node.sum_values())
 }
 
@@ -1,6 +1,6 @@
 use std::collections::{HashMap, VecDeque};
 
-use advent_of_code::number_usize;
+use advent_of_code::{number_usize, parse_all};
 use nom::{
     bytes::complete::tag,
     sequence::{terminated, tuple},
@@ -37,13 +37,13 @@ fn play_the_game(players: usize, points: usize) -> Option<usize> {
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
-    let (_, (players, points)) = parser(input).unwrap();
+    let (players, points) = parse_all(parser, input).unwrap();
 
     play_the_game(players, points)
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
-    let (_, (players, points)) = parser(input).unwrap();
+    let (players, points) = parse_all(parser, input).unwrap();
 
     play_the_game(players, points * 100)
 }
@@ -75,6 +75,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_part_two() {
         // no new task, just a brute force countermeasure for part 2
         // let result = part_two(&advent_of_code::template::read_file("examples", DAY));
@@ -3,7 +3,7 @@ advent_of_code::solution!(10);
 use std::collections::HashMap;
 use std::iter::repeat;
 
-use advent_of_code::{coord_signed_parse, CoordinateSigned};
+use advent_of_code::{coord_signed_parse, parse_all, Answer, CoordinateSigned};
 use itertools::Itertools;
 use nom::{
     bytes::complete::tag,
@@ -27,16 +27,16 @@ struct Corners {
 
 impl Corners {
     fn xs(&self) -> impl Iterator<Item = i32> {
-        self.top_left.x..=self.bottom_right.x
+        self.top_left.left..=self.bottom_right.left
     }
 
     fn ys(&self) -> impl Iterator<Item = i32> {
-        self.top_left.y..=self.bottom_right.y
+        self.top_left.top..=self.bottom_right.top
     }
 
     fn size(&self) -> i64 {
-        (self.bottom_right.x - self.top_left.x) as i64
-            * (self.bottom_right.y - self.top_left.y) as i64
+        (self.bottom_right.left - self.top_left.left) as i64
+            * (self.bottom_right.top - self.top_left.top) as i64
     }
 }
 
@@ -60,51 +60,48 @@ fn parser(i: &str) -> IResult<&str, Vec<Light>> {
 fn get_corners(lights: &[Light]) -> Corners {
     let (min_x, max_x) = lights
         .iter()
-        .map(|l| l.position.x)
+        .map(|l| l.position.left)
         .minmax()
         .into_option()
         .unwrap();
     let (min_y, max_y) = lights
         .iter()
-        .map(|l| l.position.y)
+        .map(|l| l.position.top)
         .minmax()
         .into_option()
         .unwrap();
 
     Corners {
-        top_left: CoordinateSigned { x: min_x, y: min_y },
-        bottom_right: CoordinateSigned { x: max_x, y: max_y },
+        top_left: CoordinateSigned::new(min_x, min_y),
+        bottom_right: CoordinateSigned::new(max_x, max_y),
     }
 }
 
-fn skygaze(sky: &Sky) {
-    for column in sky.corners.ys() {
-        for row in sky.corners.xs() {
-            print!("{}", sky.space.get(&(row, column)).unwrap());
-        }
-        println!();
-    }
-    println!();
+fn skygaze(sky: &Sky) -> Answer {
+    Answer::grid(sky.corners.ys().map(|column| {
+        sky.corners
+            .xs()
+            .map(|row| sky.space.get(&(row, column)).unwrap())
+            .collect::<String>()
+    }))
 }
 
 fn advance(lights: &mut [Light]) {
     // no vector iterators for us here (https://stackoverflow.com/questions/49143770/efficiently-mutate-a-vector-while-also-iterating-over-the-same-vector)
     for light in lights {
-        light.position.x += light.velocity.x;
-        light.position.y += light.velocity.y;
+        light.position += light.velocity;
     }
 }
 
 // for when we overshoot so we don't have to store the whole array at each step just in case
 fn retreat(lights: &mut [Light]) {
     for light in lights {
-        light.position.x -= light.velocity.x;
-        light.position.y -= light.velocity.y;
+        light.position -= light.velocity;
     }
 }
 
-pub fn part_one(input: &str) -> Option<String> {
-    let (_, mut lights) = parser(input).unwrap();
+pub fn part_one(input: &str) -> Option<Answer> {
+    let mut lights = parse_all(parser, input).unwrap();
 
     // find where the bounding box is the smallest
     let mut previous_size = get_corners(&lights).size();
@@ -134,17 +131,15 @@ pub fn part_one(input: &str) -> Option<String> {
     // put stars in the sky
     for light in lights.iter() {
         sky.space
-            .entry((light.position.x, light.position.y))
+            .entry((light.position.left, light.position.top))
             .and_modify(|e| *e = '#');
     }
 
-    skygaze(&sky);
-
-    Some("hi".to_string())
+    Some(skygaze(&sky))
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let (_, mut lights) = parser(input).unwrap();
+    let mut lights = parse_all(parser, input).unwrap();
 
     let mut previous_size = get_corners(&lights).size();
     let mut steps = 0;
@@ -168,9 +163,20 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        // there's not really a way to programmatically test this without a human involved or doing extra recognition work
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some("hi".to_string()));
+        assert_eq!(
+            result,
+            Some(Answer::grid([
+                "#...#..###",
+                "#...#...#.",
+                "#...#...#.",
+                "#####...#.",
+                "#...#...#.",
+                "#...#...#.",
+                "#...#...#.",
+                "#...#..###",
+            ]))
+        );
     }
 
     #[test]
@@ -1,19 +1,9 @@
 advent_of_code::solution!(11);
 
-use std::{cmp::max, collections::HashMap, fmt, str::FromStr};
+use std::{cmp::max, collections::HashMap, str::FromStr};
 
-use advent_of_code::Output;
 use itertools::Itertools;
 
-#[derive(Debug, PartialEq, Eq)]
-pub struct Output3(u32, u32, u32);
-
-impl fmt::Display for Output3 {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "({}, {}, {})", self.0, self.1, self.2)
-    }
-}
-
 fn cell_power(x: u32, y: u32, serial: u32) -> i32 {
     let rack_id = x + 10;
     let power = rack_id * y;
@@ -33,7 +23,7 @@ fn powersquare(x: u32, y: u32, grid: &HashMap<(u32, u32), i32>, size: u32) -> i3
         .sum()
 }
 
-pub fn part_one(input: &str) -> Option<Output<u32>> {
+pub fn part_one(input: &str) -> Option<(u32, u32)> {
     let serial = u32::from_str(input.trim_end()).unwrap();
     let powergrid: HashMap<(u32, u32), i32> = HashMap::from_iter(
         (1..=300)
@@ -45,10 +35,10 @@ pub fn part_one(input: &str) -> Option<Output<u32>> {
         .cartesian_product(1..=298)
         .max_by_key(|(x, y)| powersquare(*x, *y, &powergrid, 3)))
     .unwrap();
-    Some(Output(x, y))
+    Some((x, y))
 }
 
-pub fn part_two(input: &str) -> Option<Output3> {
+pub fn part_two(input: &str) -> Option<(u32, u32, u32)> {
     let serial = u32::from_str(input.trim_end()).unwrap();
     let powergrid: HashMap<(u32, u32), i32> = HashMap::from_iter(
         (1..=300)
@@ -72,7 +62,7 @@ pub fn part_two(input: &str) -> Option<Output3> {
         );
     }
 
-    let mut best = Output3(1, 1, 1);
+    let mut best = (1, 1, 1);
     let mut best_power = 0;
 
     for (x, y) in (1..=300).cartesian_product(1..=300) {
@@ -84,7 +74,7 @@ pub fn part_two(input: &str) -> Option<Output3> {
             if power > best_power {
                 best_power = power;
                 // not totally sure why we're off by one here but meh
-                best = Output3(x + 1, y + 1, s);
+                best = (x + 1, y + 1, s);
             }
         }
     }
@@ -98,7 +88,7 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(Output(33, 45)));
+        assert_eq!(result, Some((33, 45)));
     }
 
     #[test]
@@ -118,13 +108,13 @@ mod tests {
         let result = part_one(&advent_of_code::template::read_file_part(
             "examples", DAY, 1,
         ));
-        assert_eq!(result, Some(Output(21, 61)));
+        assert_eq!(result, Some((21, 61)));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(Output3(90, 269, 16)));
+        assert_eq!(result, Some((90, 269, 16)));
     }
 
     #[test]
@@ -132,6 +122,6 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file_part(
             "examples", DAY, 1,
         ));
-        assert_eq!(result, Some(Output3(232, 251, 12)));
+        assert_eq!(result, Some((232, 251, 12)));
     }
 }
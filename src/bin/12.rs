@@ -1,9 +1,6 @@
-use std::{
-    collections::{HashMap, HashSet},
-    fmt,
-    hash::Hash,
-};
+use std::{collections::HashSet, fmt, hash::Hash};
 
+use advent_of_code::{cycle::fast_forward_value, parse_all};
 use itertools::Itertools;
 use nom::{
     branch::alt,
@@ -15,8 +12,6 @@ use nom::{
     IResult,
 };
 
-use frozenset::{Freeze, FrozenSet};
-
 advent_of_code::solution!(12);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -82,7 +77,7 @@ fn next_state(current_state: &HashSet<i32>, rules: &HashSet<Rule>) -> HashSet<i3
 }
 
 pub fn part_one(input: &str) -> Option<i32> {
-    let (_, (initial, rules)) = parser(input).unwrap();
+    let (initial, rules) = parse_all(parser, input).unwrap();
     // map the initial vector into just the pot locations
     let mut state = HashSet::from_iter(
         initial
@@ -100,49 +95,31 @@ pub fn part_one(input: &str) -> Option<i32> {
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    let (_, (initial, rules)) = parser(input).unwrap();
-    let mut state = HashSet::from_iter(
+    let (initial, rules) = parse_all(parser, input).unwrap();
+    let state: HashSet<i32> = HashSet::from_iter(
         initial
             .into_iter()
             .enumerate()
             .filter(|(_, x)| matches!(x, Pot::P))
             .map(|(i, _)| i.try_into().unwrap()),
     );
-    let mut seen_states: HashMap<FrozenSet<i32>, (u64, i32)> = HashMap::new();
-
-    for i in 1..50_000_000_000_u64 {
-        state = next_state(&state, &rules);
-        // frozen sets are hashable and read only, which is what we want for membership checking
-        // normalize our state by subtracting the minimum from everything to get a 0-centered representation
-        let normalized_state = state
-            .iter()
-            .map(|s| s - state.iter().min().unwrap())
-            .collect::<HashSet<i32>>()
-            .freeze();
-
-        if let std::collections::hash_map::Entry::Vacant(e) =
-            seen_states.entry(normalized_state.clone())
-        {
-            e.insert((i, state.iter().sum()));
-        } else {
-            // the previous seen_state gives the stepwise increase
-            let (old_step, old_value) = seen_states.get(&normalized_state).unwrap();
-            let increment = i - old_step;
-            let current_value = state.iter().sum::<i32>();
-            let value_increment = (current_value - old_value) as u64;
-
-            // turns out our clues give an increment of 1, so a recurring shape that just increases by a fixed value every step
-            // so skipping the logic to factor out cycles and offsets near the 50 billion threshold
-            // and just doing basic math once it collapses to that configuration
-
-            let steps_remaining = 50_000_000_000 - i;
-            let future_score = steps_remaining / increment * value_increment;
-
-            return Some((current_value as u64) + future_score);
-        }
-    }
 
-    None
+    // the plants settle into a shape that keeps moving along, so compare the shapes by
+    // subtracting the leftmost pot from everything to get a 0-based representation
+    let shape = |state: &HashSet<i32>| {
+        let min = state.iter().min().copied().unwrap_or_default();
+        state.iter().map(|s| s - min).sorted().collect_vec()
+    };
+    let sum = |state: &HashSet<i32>| state.iter().map(|&s| i64::from(s)).sum();
+
+    let total = fast_forward_value(
+        state,
+        50_000_000_000,
+        |state| next_state(state, &rules),
+        shape,
+        sum,
+    );
+    total.try_into().ok()
 }
 
 #[cfg(test)]
@@ -156,6 +133,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_part_two() {
         let _ = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert!(true);
@@ -4,12 +4,12 @@ use std::{
     fmt,
 };
 
-use advent_of_code::{Coordinate, Output};
+use advent_of_code::{Coordinate, Grid};
 use itertools::Itertools;
 
 advent_of_code::solution!(13);
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum Feature {
     FsCorner,
     BsCorner,
@@ -32,7 +32,7 @@ enum Cart {
     Right(Cycle),
 }
 
-type CartMap = HashMap<Coordinate<usize>, Feature>;
+type CartMap = Grid<Option<Feature>>;
 
 type AllCarts = HashMap<Coordinate<usize>, Cart>;
 
@@ -49,19 +49,25 @@ impl fmt::Display for CartCrash {
 
 // not really a token based parser so just doing it with string manipulation instead of nom
 fn parser(i: &str) -> (CartMap, AllCarts) {
-    let mut map = CartMap::new();
+    // trailing spaces may have been trimmed, so size the map by its longest line
+    let width = i
+        .lines()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut map = CartMap::new(width, i.lines().count(), None);
     let mut state = AllCarts::new();
     for (top, line) in i.lines().enumerate() {
         for (left, character) in line.chars().enumerate() {
             match character {
                 '/' => {
-                    map.insert(Coordinate { left, top }, Feature::FsCorner);
+                    map[Coordinate { left, top }] = Some(Feature::FsCorner);
                 }
                 '\\' => {
-                    map.insert(Coordinate { left, top }, Feature::BsCorner);
+                    map[Coordinate { left, top }] = Some(Feature::BsCorner);
                 }
                 '+' => {
-                    map.insert(Coordinate { left, top }, Feature::Intersection);
+                    map[Coordinate { left, top }] = Some(Feature::Intersection);
                 }
                 '^' => {
                     state.insert(Coordinate { left, top }, Cart::Up(Cycle::Left));
@@ -107,7 +113,7 @@ fn new_location(coord: &Coordinate<usize>, facing: &Cart) -> Coordinate<usize> {
 }
 
 fn new_facing(coord: &Coordinate<usize>, cart: &Cart, map: &CartMap) -> Cart {
-    match map.get(coord) {
+    match map.get(*coord).and_then(Option::as_ref) {
         Some(Feature::FsCorner) => match cart {
             Cart::Up(c) => Cart::Right(*c),
             Cart::Down(c) => Cart::Left(*c),
@@ -209,39 +215,38 @@ fn next_state_removal(state: &AllCarts, map: &CartMap) -> AllCarts {
     next_state
 }
 
-pub fn part_one(input: &str) -> Option<Output<usize>> {
+pub fn part_one(input: &str) -> Option<(usize, usize)> {
     let (map, mut state) = parser(input);
 
     loop {
         match next_state(&state, &map) {
             Ok(s) => state = s,
-            Err(CartCrash(c)) => return Some(Output(c.left, c.top)),
+            Err(CartCrash(c)) => return Some((c.left, c.top)),
         }
     }
 }
 
-pub fn part_two(input: &str) -> Option<Output<usize>> {
+pub fn part_two(input: &str) -> Option<(usize, usize)> {
     let (map, mut state) = parser(input);
 
     loop {
         state = next_state_removal(&state, &map);
         if state.len() == 1 {
             let (only_element, _) = state.drain().take(1).next().unwrap();
-            return Some(Output(only_element.left, only_element.top));
+            return Some((only_element.left, only_element.top));
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use advent_of_code::Output;
 
     use super::*;
 
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(Output(7, 3)));
+        assert_eq!(result, Some((7, 3)));
     }
 
     #[test]
@@ -249,7 +254,7 @@ mod tests {
         let result = part_one(&advent_of_code::template::read_file_part(
             "examples", DAY, 1,
         ));
-        assert_eq!(result, Some(Output(0, 3)));
+        assert_eq!(result, Some((0, 3)));
     }
 
     #[test]
@@ -257,6 +262,6 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file_part(
             "examples", DAY, 2,
         ));
-        assert_eq!(result, Some(Output(6, 4)));
+        assert_eq!(result, Some((6, 4)));
     }
 }
@@ -10,6 +10,8 @@ use std::{
     fmt,
 };
 
+use advent_of_code::Coordinate;
+
 #[derive(Debug, Clone, Copy)]
 pub struct Barf();
 
@@ -21,38 +23,9 @@ impl fmt::Display for Barf {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
-pub struct Pos(i64, i64);
-type Order = (i64, i64);
+pub type Pos = Coordinate<i64>;
 pub type UnitId = usize;
 
-impl Pos {
-    /// Get the order of the given position.
-    pub fn order(self) -> Order {
-        let Pos(x, y) = self;
-        (y, x)
-    }
-
-    /// Get a collection of neighbours based on the current position.
-    pub fn neighs(self) -> impl Iterator<Item = Pos> {
-        let Pos(x, y) = self;
-        vec![Pos(x, y - 1), Pos(x - 1, y), Pos(x + 1, y), Pos(x, y + 1)].into_iter()
-    }
-
-    /// The difference between this compared to another position.
-    pub fn delta(self, other: Pos) -> (i64, i64) {
-        let Pos(x0, y0) = self;
-        let Pos(x1, y1) = other;
-        ((x1 - x0).abs(), (y1 - y0).abs())
-    }
-
-    /// The distance between two points.
-    pub fn distance(self, other: Pos) -> i64 {
-        let (x, y) = self.delta(other);
-        x + y
-    }
-}
-
 #[derive(Debug, Clone, Default)]
 pub struct State {
     position_by_unit: HashMap<UnitId, Pos>,
@@ -75,10 +48,10 @@ impl State {
 
         for (y, line) in input.lines().enumerate() {
             for (x, c) in line.chars().enumerate() {
-                let pos = Pos(x as i64, y as i64);
+                let pos = Pos::new(x as i64, y as i64);
 
-                state.width = i64::max(state.width, pos.0);
-                state.height = i64::max(state.height, pos.1);
+                state.width = i64::max(state.width, pos.left);
+                state.height = i64::max(state.height, pos.top);
 
                 let kind = match c {
                     '#' => {
@@ -173,7 +146,7 @@ impl State {
             .map(|(id, p)| (*id, *p))
             .collect::<Vec<_>>();
 
-        units.sort_by_key(|(_, p)| p.order());
+        units.sort_by_key(|(_, p)| *p);
         units.into_iter().map(|(id, _)| id).collect::<Vec<_>>()
     }
 
@@ -231,7 +204,7 @@ impl State {
                     }
                 }
 
-                queue.extend(p.neighs().map(|n| (n, d + 1)));
+                queue.extend(p.neighbours_4().map(|n| (n, d + 1)));
             }
 
             found
@@ -267,12 +240,12 @@ impl State {
                 }
             }
 
-            queue.extend(p.neighs().map(|n| (n, d + 1)));
+            queue.extend(p.neighbours_4().map(|n| (n, d + 1)));
         }
 
         let mut candidates = Vec::new();
 
-        for n in from.neighs() {
+        for n in from.neighbours_4() {
             if let Some(d) = dist.get(&n).cloned() {
                 if d == target_distance - 1 {
                     candidates.push(n);
@@ -280,7 +253,7 @@ impl State {
             }
         }
 
-        candidates.sort_by_key(|c| c.order());
+        candidates.sort();
         candidates.into_iter().next()
     }
 
@@ -300,7 +273,7 @@ impl State {
 
             let other_pos = self.find_unit_position(target_id)?;
 
-            if my_pos.distance(other_pos) == 1 {
+            if my_pos.manhattan(other_pos) == 1 {
                 attack = match attack {
                     Some((_, hit_points)) if hit_points > target_unit.hit_points => {
                         Some((target_id, target_unit.hit_points))
@@ -512,7 +485,7 @@ impl fmt::Display for Display<'_> {
 
         for y in 0..=state.height {
             for x in 0..=state.width {
-                let p = Pos(x, y);
+                let p = Pos::new(x, y);
 
                 match state.walls.contains(&p) {
                     true => "🧱".fmt(fmt)?,
@@ -537,6 +510,7 @@ mod tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_part_one() {
         // not setting up automated tests for not my code
         // let result = part_one(&advent_of_code::template::read_file("examples", DAY));
@@ -544,6 +518,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_part_two() {
         // let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert!(true);
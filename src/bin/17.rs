@@ -1,7 +1,7 @@
 use core::fmt;
-use std::{collections::HashMap, fs::File, io::Write, iter::once};
+use std::{fs::File, io::Write, iter::once};
 
-use advent_of_code::{number, Coordinate};
+use advent_of_code::{number, parse_all, Coordinate, Grid};
 use itertools::Itertools;
 use nom::{
     branch::alt,
@@ -15,11 +15,12 @@ use nom::{
 advent_of_code::solution!(17);
 
 type Vein = Vec<Coordinate<u32>>;
-type ReservoirContents = HashMap<Coordinate<u32>, Ground>;
 
 // add bounds metadata so we don't have to keep recalculating
 struct Reservoir {
-    contents: ReservoirContents,
+    // only covers the columns from `origin` on, everything outside of it is sand
+    contents: Grid<Ground>,
+    origin: u32,
     left_edge: u32,
     right_edge: u32,
     bottom_edge: u32,
@@ -27,18 +28,34 @@ struct Reservoir {
 }
 
 impl Reservoir {
+    fn cell(&self, location: &Coordinate<u32>) -> Option<Coordinate<usize>> {
+        Some(Coordinate {
+            left: location.left.checked_sub(self.origin)? as usize,
+            top: location.top as usize,
+        })
+    }
+
     fn below(&self, location: &Coordinate<u32>) -> Ground {
-        *self
-            .contents
-            .get(&Coordinate {
-                left: location.left,
-                top: location.top + 1,
-            })
-            .unwrap_or(&Ground::Sand)
+        self.at(&Coordinate {
+            left: location.left,
+            top: location.top + 1,
+        })
     }
 
     fn at(&self, location: &Coordinate<u32>) -> Ground {
-        *self.contents.get(location).unwrap_or(&Ground::Sand)
+        self.cell(location)
+            .and_then(|c| self.contents.get(c))
+            .copied()
+            .unwrap_or(Ground::Sand)
+    }
+
+    /// Returns what was there before.
+    fn set(&mut self, location: &Coordinate<u32>, ground: Ground) -> Ground {
+        let previous = self.at(location);
+        if let Some(c) = self.cell(location) {
+            self.contents.set(c, ground);
+        }
+        previous
     }
 }
 
@@ -129,16 +146,11 @@ fn check_stable(
 fn fill_row(active: &Coordinate<u32>, reservoir: &mut Reservoir) -> Filled {
     macro_rules! pairs_to_next_clay {
         ($x:expr) => {
-            $x.map(|y| {
-                reservoir.contents.get_key_value(&Coordinate {
-                    top: active.top,
-                    left: y,
-                })
-            })
-            .find_map(|c| match c {
-                Some(c) if *c.1 == Ground::Clay => Some(c),
-                _ => None,
+            $x.map(|y| Coordinate {
+                top: active.top,
+                left: y,
             })
+            .find(|c| reservoir.at(c) == Ground::Clay)
         };
     }
 
@@ -148,10 +160,10 @@ fn fill_row(active: &Coordinate<u32>, reservoir: &mut Reservoir) -> Filled {
     match left_side.zip(right_side) {
         // if the path between both edges is stable, fill
         Some((left_edge, right_edge)) => {
-            if check_stable(left_edge.0, right_edge.0, reservoir) {
-                for spot in (left_edge.0.left + 1)..right_edge.0.left {
-                    reservoir.contents.insert(
-                        Coordinate {
+            if check_stable(&left_edge, &right_edge, reservoir) {
+                for spot in (left_edge.left + 1)..right_edge.left {
+                    reservoir.set(
+                        &Coordinate {
                             left: spot,
                             top: active.top,
                         },
@@ -194,7 +206,7 @@ fn overflow_row(
 
     let mut anything_changed = false;
     for coord in once(active).chain(left_side.iter().chain(right_side.iter())) {
-        if reservoir.contents.insert(*coord, Ground::Wet).is_none() {
+        if reservoir.set(coord, Ground::Wet) == Ground::Sand {
             anything_changed = true;
         }
     }
@@ -214,7 +226,7 @@ fn fill(mut active: Coordinate<u32>, reservoir: &mut Reservoir) {
     while matches!(reservoir.at(&active), Ground::Sand | Ground::Wet)
         && active.top <= reservoir.bottom_edge
     {
-        reservoir.contents.insert(active, Ground::Wet);
+        reservoir.set(&active, Ground::Wet);
         if active.top == reservoir.bottom_edge {
             return;
         } else {
@@ -248,38 +260,47 @@ fn fill(mut active: Coordinate<u32>, reservoir: &mut Reservoir) {
 }
 
 fn make_reservoir(veins: Vec<Vein>) -> Reservoir {
-    let mut contents: ReservoirContents =
-        HashMap::from_iter(veins.into_iter().flatten().zip(once(Ground::Clay).cycle()));
+    let clay = veins.into_iter().flatten().collect_vec();
 
     // calculate scoring bounds before we add our start point
-    let (min_x, max_x) = contents
-        .keys()
-        .map(|c| c.left)
-        .minmax()
-        .into_option()
-        .unwrap();
-    let (min_y, max_y) = contents
-        .keys()
-        .map(|c| c.top)
-        .minmax()
-        .into_option()
-        .unwrap();
-
-    contents.insert(Coordinate { left: 500, top: 0 }, Ground::Spring);
-    contents.insert(Coordinate { left: 500, top: 1 }, Ground::Wet);
-
-    Reservoir {
-        contents,
+    let (min_x, max_x) = clay.iter().map(|c| c.left).minmax().into_option().unwrap();
+    let (min_y, max_y) = clay.iter().map(|c| c.top).minmax().into_option().unwrap();
+
+    // side edges need to be expanded one column to allow for overflow, and one more to print
+    let origin = min_x - 2;
+    let mut reservoir = Reservoir {
+        contents: Grid::new(
+            (max_x + 2 - origin) as usize,
+            max_y as usize + 1,
+            Ground::Sand,
+        ),
+        origin,
         bottom_edge: max_y,
         top_edge: min_y,
-        // side edges need to be expanded one column to allow for overflow
         left_edge: min_x - 1,
         right_edge: max_x + 1,
+    };
+
+    for c in &clay {
+        reservoir.set(c, Ground::Clay);
     }
+    reservoir.set(&Coordinate { left: 500, top: 0 }, Ground::Spring);
+    reservoir.set(&Coordinate { left: 500, top: 1 }, Ground::Wet);
+
+    reservoir
+}
+
+/// The tiles within the scanned rows, since we're modeling water above their bounds.
+fn scanned(reservoir: &Reservoir) -> impl Iterator<Item = Ground> + '_ {
+    reservoir
+        .contents
+        .iter()
+        .filter(|(c, _)| c.top >= reservoir.top_edge as usize)
+        .map(|(_, g)| *g)
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
-    let (_, veins) = parser(input).unwrap();
+    let veins = parse_all(parser, input).unwrap();
     let mut reservoir = make_reservoir(veins);
 
     let start = Coordinate { left: 500, top: 1 };
@@ -289,19 +310,14 @@ pub fn part_one(input: &str) -> Option<usize> {
     write!(&mut w, "{}", reservoir).unwrap();
 
     Some(
-        reservoir
-            .contents
-            .into_iter()
-            // within the grid, since we're modeling water above their bounds
-            .filter(|(c, _)| c.top >= reservoir.top_edge)
-            .map(|(_, g)| g)
+        scanned(&reservoir)
             .filter(|&g| g.eq(&Ground::Flooded) || g.eq(&Ground::Wet))
             .count(),
     )
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
-    let (_, veins) = parser(input).unwrap();
+    let veins = parse_all(parser, input).unwrap();
     let mut reservoir = make_reservoir(veins);
 
     let start = Coordinate { left: 500, top: 1 };
@@ -311,11 +327,7 @@ pub fn part_two(input: &str) -> Option<usize> {
     write!(&mut w, "{}", reservoir).unwrap();
 
     Some(
-        reservoir
-            .contents
-            .into_iter()
-            .filter(|(c, _)| c.top >= reservoir.top_edge)
-            .map(|(_, g)| g)
+        scanned(&reservoir)
             // all that changes is we're only looking for flooded tiles
             .filter(|&g| g.eq(&Ground::Flooded))
             .count(),
@@ -45,7 +45,7 @@ fn parser(input: &str) -> Vec<Step> {
 
 fn make_graph(steps: Vec<Step>) -> Graph<CoordinateSigned, u32, Undirected> {
     let mut graph = Graph::<CoordinateSigned, u32, Undirected>::new_undirected();
-    let mut location = CoordinateSigned { x: 0, y: 0 };
+    let mut location = CoordinateSigned::new(0, 0);
     let mut coord_to_idx: HashMap<CoordinateSigned, NodeIndex> = HashMap::new();
 
     let mut stack_frame = Vec::new();
@@ -59,10 +59,10 @@ fn make_graph(steps: Vec<Step>) -> Graph<CoordinateSigned, u32, Undirected> {
                     .or_insert_with(|| graph.add_node(location));
 
                 match direction {
-                    Direction::North => location.y += 1,
-                    Direction::East => location.x += 1,
-                    Direction::South => location.y -= 1,
-                    Direction::West => location.x -= 1,
+                    Direction::North => location.top += 1,
+                    Direction::East => location.left += 1,
+                    Direction::South => location.top -= 1,
+                    Direction::West => location.left -= 1,
                 };
                 let current_idx = *coord_to_idx
                     .entry(location)
@@ -141,6 +141,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_part_two() {
         // let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         // none of our test cases are relevant to check 1000+ path lengths
@@ -1,13 +1,8 @@
 use std::collections::HashMap;
 
-use advent_of_code::{coord_parse_usize, number_usize, Coordinate};
+use advent_of_code::{coord_parse_usize, labelled, parse_all, unsigned, Coordinate, Grid};
 use itertools::Itertools;
-use nom::{
-    bytes::complete::tag,
-    character::complete::newline,
-    sequence::{preceded, terminated},
-    IResult,
-};
+use nom::{character::complete::newline, sequence::terminated, IResult};
 use petgraph::{algo::dijkstra, Graph, Undirected};
 
 advent_of_code::solution!(22);
@@ -46,21 +41,20 @@ impl Terrain {
 type Position = (Coordinate<usize>, Equipment);
 
 fn parser(i: &str) -> IResult<&str, Cave> {
-    let (i, depth) = terminated(preceded(tag("depth: "), number_usize), newline)(i)?;
-    let (i, target) = preceded(tag("target: "), coord_parse_usize)(i)?;
+    let (i, depth) = terminated(labelled("depth", unsigned()), newline)(i)?;
+    let (i, target) = labelled("target", coord_parse_usize)(i)?;
 
     Ok((i, Cave { depth, target }))
 }
 
-fn _print_cavern(cave: &Cave, cavern: &HashMap<Coordinate<usize>, usize>) {
+fn _print_cavern(cave: &Cave, cavern: &Grid<usize>) {
     for row in 0..=cave.target.top {
         for col in 0..=cave.target.left {
-            let x = cavern
-                .get(&Coordinate {
-                    left: col,
-                    top: row,
-                })
-                .unwrap();
+            let x = cavern[Coordinate {
+                left: col,
+                top: row,
+            }];
+            #[allow(clippy::manual_is_multiple_of)]
             let character = if row == cave.target.top && col == cave.target.left {
                 "T"
             } else if x % 3 == 0 {
@@ -79,60 +73,53 @@ fn _print_cavern(cave: &Cave, cavern: &HashMap<Coordinate<usize>, usize>) {
     println!();
 }
 
-fn build_cavern(cave: &Cave, buffer: usize) -> HashMap<Coordinate<usize>, usize> {
-    let mut cavern: HashMap<Coordinate<usize>, usize> = HashMap::new();
-    // sorted by minimum sum which will give numbers closer to the top left corner first
-    for (left, top) in (0..=cave.target.left + buffer)
-        .cartesian_product(0..=cave.target.top + buffer)
-        .sorted_by_key(|(x, y)| x + y)
-    {
+fn build_cavern(cave: &Cave, buffer: usize) -> Grid<usize> {
+    let mut cavern = Grid::new(
+        cave.target.left + buffer + 1,
+        cave.target.top + buffer + 1,
+        0,
+    );
+    // in reading order, the regions to the left and above are always done first
+    for (top, left) in (0..cavern.height()).cartesian_product(0..cavern.width()) {
         let geologic_index = match (left, top) {
             (0, 0) => 0,
             (x, y) if x == cave.target.left && y == cave.target.top => 0,
             (x, 0) => x * 16807,
             (0, y) => y * 48271,
             (x, y) => {
-                cavern
-                    .get(&Coordinate {
-                        left: x - 1,
-                        top: y,
-                    })
-                    .unwrap()
-                    * cavern
-                        .get(&Coordinate {
-                            left: x,
-                            top: y - 1,
-                        })
-                        .unwrap()
+                cavern[Coordinate {
+                    left: x - 1,
+                    top: y,
+                }] * cavern[Coordinate {
+                    left: x,
+                    top: y - 1,
+                }]
             }
         };
         // move the modulo part up since otherwise we'll quickly overflow
         // since we're modding everything before using it, it's equivalent
         // (i tried bigints too and it was not fast enough lol)
-        cavern.insert(
-            Coordinate { left, top },
-            (geologic_index + cave.depth) % 20183,
-        );
+        cavern[Coordinate { left, top }] = (geologic_index + cave.depth) % 20183;
     }
 
     cavern
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
-    let (_, cave) = parser(input).unwrap();
+    let cave = parse_all(parser, input).unwrap();
 
     let cavern = build_cavern(&cave, 0);
 
     Some(
         (0..=cave.target.left)
             .cartesian_product(0..=cave.target.top)
-            .map(|(left, top)| cavern.get(&Coordinate { left, top }).unwrap() % 3)
+            .map(|(left, top)| cavern[Coordinate { left, top }] % 3)
             .sum(),
     )
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let (_, cave) = parser(input).unwrap();
+    let cave = parse_all(parser, input).unwrap();
 
     // experimentally determined, sigh
     let buffer = 30;
@@ -152,7 +139,7 @@ pub fn part_two(input: &str) -> Option<u32> {
         (0..=cave.target.left + buffer - 1).cartesian_product(0..=cave.target.top + buffer - 1)
     {
         let coords = Coordinate { left, top };
-        let terrain = Terrain::from_usize(*cavern.get(&coords).unwrap());
+        let terrain = Terrain::from_usize(cavern[coords]);
 
         // self transitions
         match terrain {
@@ -178,7 +165,7 @@ pub fn part_two(input: &str) -> Option<u32> {
         macro_rules! add_edges {
             ($x: expr, $y: expr) => {
                 let neighbor_coords = Coordinate { left: $x, top: $y };
-                let neighbor_terrain = Terrain::from_usize(*cavern.get(&neighbor_coords).unwrap());
+                let neighbor_terrain = Terrain::from_usize(cavern[neighbor_coords]);
                 match (terrain.clone(), neighbor_terrain) {
                     (Terrain::Rocky, Terrain::Rocky) => {
                         let e1 = get_or_make!((coords, Equipment::ClimbingGear));
@@ -1,35 +1,30 @@
 use std::collections::BTreeMap;
 
+use advent_of_code::{parse_all, Point};
 use nom::{
     bytes::complete::tag,
     character::complete::{i32, newline},
-    multi::{separated_list0, separated_list1},
+    multi::separated_list0,
     sequence::preceded,
     IResult,
 };
 
-use itertools::Itertools;
-
 advent_of_code::solution!(23);
 
 #[derive(Debug)]
 struct Nanobot {
-    position: (i32, i32, i32),
+    position: Point<i32, 3>,
     radius: i32,
 }
 
 impl Nanobot {
-    fn in_range(&self, other: &(i32, i32, i32)) -> bool {
-        let distance = (self.position.0 - other.0).abs()
-            + (self.position.1 - other.1).abs()
-            + (self.position.2 - other.2).abs();
-        distance <= self.radius
+    fn in_range(&self, other: &Point<i32, 3>) -> bool {
+        self.position.manhattan(*other) <= self.radius
     }
 }
 
 fn one_nanobot(i: &str) -> IResult<&str, Nanobot> {
-    let (i, position) = preceded(tag("pos=<"), separated_list1(tag(","), i32))(i)?;
-    let position = position.into_iter().collect_tuple().unwrap();
+    let (i, position) = preceded(tag("pos=<"), Point::parse)(i)?;
     let (i, radius) = preceded(tag(">, r="), i32)(i)?;
 
     Ok((i, Nanobot { position, radius }))
@@ -40,7 +35,7 @@ fn parser(i: &str) -> IResult<&str, Vec<Nanobot>> {
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
-    let (_, nanobots) = parser(input).unwrap();
+    let nanobots = parse_all(parser, input).unwrap();
 
     let biggest = nanobots.iter().max_by_key(|n| n.radius).unwrap();
     let in_range = nanobots
@@ -52,7 +47,7 @@ pub fn part_one(input: &str) -> Option<usize> {
 }
 
 pub fn part_two(input: &str) -> Option<i32> {
-    let (_, nanobots) = parser(input).unwrap();
+    let nanobots = parse_all(parser, input).unwrap();
 
     // tried to do a z3-based solution based on https://www.reddit.com/r/adventofcode/comments/a8s17l/2018_day_23_solutions/ecdbux2/
     // and https://cprimozic.net/blog/a-rusty-aoc/ which would have been perfect
@@ -62,7 +57,7 @@ pub fn part_two(input: &str) -> Option<i32> {
     // adapted from https://www.reddit.com/r/adventofcode/comments/a8s17l/2018_day_23_solutions/ecespv2/
     let mut dist = BTreeMap::new();
     for nanobot in nanobots {
-        let d = nanobot.position.0 + nanobot.position.1 + nanobot.position.2;
+        let d: i32 = nanobot.position.0.iter().sum();
         *dist.entry(d - nanobot.radius).or_insert(0) += 1;
         *dist.entry(d + nanobot.radius + 1).or_insert(0) -= 1;
     }
@@ -4,7 +4,7 @@ use std::{
     str::FromStr,
 };
 
-use advent_of_code::number;
+use advent_of_code::{number, parse_all};
 use frozenset::FrozenSet;
 use itertools::Itertools;
 use nom::{
@@ -293,7 +293,7 @@ fn fight(groups: &HashMap<usize, Group>, debug: bool) -> HashMap<usize, Group> {
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let (_, battle) = parser(input).unwrap();
+    let battle = parse_all(parser, input).unwrap();
     let mut battle = HashMap::from_iter(battle.into_iter().enumerate());
 
     loop {
@@ -308,7 +308,7 @@ pub fn part_one(input: &str) -> Option<u32> {
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let (_, battle) = parser(input).unwrap();
+    let battle = parse_all(parser, input).unwrap();
     let mut battle = HashMap::from_iter(battle.into_iter().enumerate());
 
     for i in 1.. {
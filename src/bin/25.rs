@@ -1,30 +1,17 @@
 use std::{
     collections::{HashMap, HashSet},
-    hash::{Hash, RandomState},
+    hash::RandomState,
 };
 
-use nom::{
-    bytes::complete::tag,
-    character::complete::{i32, newline},
-    multi::{separated_list0, separated_list1},
-    IResult,
-};
-
-use itertools::Itertools;
-advent_of_code::solution!(25);
+use advent_of_code::{parse_all, Point};
+use nom::{character::complete::newline, multi::separated_list0, IResult};
 
-#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
-struct FourD(i32, i32, i32, i32);
+advent_of_code::solution!(25, single_part);
 
-fn distance(a: &FourD, b: &FourD) -> i32 {
-    (a.0 - b.0).abs() + (a.1 - b.1).abs() + (a.2 - b.2).abs() + (a.3 - b.3).abs()
-}
+type FourD = Point<i32, 4>;
 
 fn parse_fourd(i: &str) -> IResult<&str, FourD> {
-    let (i, coords) = separated_list1(tag(","), i32)(i)?;
-    let (&x, &y, &z, &q) = coords.iter().collect_tuple().unwrap();
-
-    Ok((i, FourD(x, y, z, q)))
+    Point::parse(i)
 }
 
 fn parser(i: &str) -> IResult<&str, Vec<FourD>> {
@@ -32,7 +19,7 @@ fn parser(i: &str) -> IResult<&str, Vec<FourD>> {
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
-    let (_, coords) = parser(input).unwrap();
+    let coords = parse_all(parser, input).unwrap();
 
     let mut constellations = HashMap::new();
     constellations.insert(0, HashSet::from([coords[0]]));
@@ -40,7 +27,7 @@ pub fn part_one(input: &str) -> Option<usize> {
     for coord in &coords[1..] {
         let neighbors = constellations
             .iter()
-            .filter(|(_, c)| c.iter().any(|s| distance(coord, s) <= 3))
+            .filter(|(_, c)| c.iter().any(|s| coord.manhattan(*s) <= 3))
             .collect::<Vec<_>>();
 
         // new constellation
@@ -73,10 +60,6 @@ pub fn part_one(input: &str) -> Option<usize> {
     Some(constellations.len())
 }
 
-pub fn part_two(_input: &str) -> Option<u32> {
-    None
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -84,7 +67,7 @@ mod tests {
     #[test]
     fn test_parse() {
         let result = parse_fourd("-1,2,2,0");
-        assert_eq!(result, Ok(("", FourD(-1, 2, 2, 0))));
+        assert_eq!(result, Ok(("", Point([-1, 2, 2, 0]))));
     }
 
     #[test]
@@ -92,7 +75,7 @@ mod tests {
         let result = parser("-1,2,2,0\n0,0,2,-2");
         assert_eq!(
             result,
-            Ok(("", vec![FourD(-1, 2, 2, 0), FourD(0, 0, 2, -2)]))
+            Ok(("", vec![Point([-1, 2, 2, 0]), Point([0, 0, 2, -2])]))
         );
     }
 
@@ -125,10 +108,4 @@ mod tests {
         ));
         assert_eq!(result, Some(8));
     }
-
-    #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
-    }
 }
@@ -0,0 +1,292 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign},
+};
+
+/// The integer types a [`Coordinate`] can be made of.
+pub trait Integer:
+    Copy
+    + Ord
+    + fmt::Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+
+    /// The absolute difference, which does not overflow for unsigned types.
+    fn abs_diff(self, other: Self) -> Self {
+        if self > other {
+            self - other
+        } else {
+            other - self
+        }
+    }
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+
+                fn checked_sub(self, other: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// A point on a 2D grid. `left` grows to the right and `top` grows downwards.
+///
+/// Coordinates are ordered in reading order: top to bottom, then left to right.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct Coordinate<T> {
+    pub left: T,
+    pub top: T,
+}
+
+impl<T> Coordinate<T> {
+    pub const fn new(left: T, top: T) -> Self {
+        Coordinate { left, top }
+    }
+}
+
+impl<T: Integer> Coordinate<T> {
+    /// The Manhattan, or taxicab, distance between two coordinates.
+    pub fn manhattan(self, other: Self) -> T {
+        self.left.abs_diff(other.left) + self.top.abs_diff(other.top)
+    }
+
+    /// The Chebyshev, or chessboard, distance between two coordinates.
+    pub fn chebyshev(self, other: Self) -> T {
+        self.left
+            .abs_diff(other.left)
+            .max(self.top.abs_diff(other.top))
+    }
+
+    fn offset(self, left: Ordering, top: Ordering) -> Option<Self> {
+        let step = |value: T, direction| match direction {
+            Ordering::Less => value.checked_sub(T::ONE),
+            Ordering::Equal => Some(value),
+            Ordering::Greater => value.checked_add(T::ONE),
+        };
+        Some(Coordinate {
+            left: step(self.left, left)?,
+            top: step(self.top, top)?,
+        })
+    }
+
+    /// The orthogonal neighbours in reading order, skipping those that do not fit in `T`.
+    pub fn neighbours_4(self) -> impl Iterator<Item = Self> {
+        use Ordering::*;
+        [
+            (Equal, Less),
+            (Less, Equal),
+            (Greater, Equal),
+            (Equal, Greater),
+        ]
+        .into_iter()
+        .filter_map(move |(left, top)| self.offset(left, top))
+    }
+
+    /// The orthogonal and diagonal neighbours in reading order, skipping those that do not fit in `T`.
+    pub fn neighbours_8(self) -> impl Iterator<Item = Self> {
+        use Ordering::*;
+        [Less, Equal, Greater]
+            .into_iter()
+            .flat_map(|top| [Less, Equal, Greater].map(|left| (left, top)))
+            .filter(|&(left, top)| (left, top) != (Equal, Equal))
+            .filter_map(move |(left, top)| self.offset(left, top))
+    }
+
+    /// Returns true if the coordinate lies in the rectangle from the origin up to, but excluding, `bounds`.
+    pub fn within(self, bounds: Self) -> bool {
+        T::ZERO <= self.left
+            && self.left < bounds.left
+            && T::ZERO <= self.top
+            && self.top < bounds.top
+    }
+
+    /// Convert to a coordinate of another integer type, e.g. from `usize` to `i64`.
+    /// Returns `None` if a component does not fit.
+    pub fn convert<U: TryFrom<T>>(self) -> Option<Coordinate<U>> {
+        Some(Coordinate {
+            left: U::try_from(self.left).ok()?,
+            top: U::try_from(self.top).ok()?,
+        })
+    }
+}
+
+impl<T: Integer> Ord for Coordinate<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.top, self.left).cmp(&(other.top, other.left))
+    }
+}
+
+impl<T: Integer> PartialOrd for Coordinate<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Coordinate<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{}", self.left, self.top)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl<T: Add<Output = T>> Add for Coordinate<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Coordinate {
+            left: self.left + other.left,
+            top: self.top + other.top,
+        }
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Coordinate<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Coordinate {
+            left: self.left - other.left,
+            top: self.top - other.top,
+        }
+    }
+}
+
+impl<T: Add<Output = T> + Copy> AddAssign for Coordinate<T> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Sub<Output = T> + Copy> SubAssign for Coordinate<T> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Coordinate<T> {
+    type Output = Self;
+
+    fn mul(self, scalar: T) -> Self {
+        Coordinate {
+            left: self.left * scalar,
+            top: self.top * scalar,
+        }
+    }
+}
+
+impl<T: Div<Output = T> + Copy> Div<T> for Coordinate<T> {
+    type Output = Self;
+
+    fn div(self, scalar: T) -> Self {
+        Coordinate {
+            left: self.left / scalar,
+            top: self.top / scalar,
+        }
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Coordinate<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Coordinate {
+            left: -self.left,
+            top: -self.top,
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Coordinate;
+
+    #[test]
+    fn does_arithmetic() {
+        let a = Coordinate::new(3_i32, -2);
+        let b = Coordinate::new(-1, 5);
+        assert_eq!(a + b, Coordinate::new(2, 3));
+        assert_eq!(a - b, Coordinate::new(4, -7));
+        assert_eq!(a * 2, Coordinate::new(6, -4));
+        assert_eq!(-a, Coordinate::new(-3, 2));
+        assert_eq!(a.manhattan(b), 11);
+        assert_eq!(a.chebyshev(b), 7);
+        assert_eq!(
+            Coordinate::new(1_u32, 8).manhattan(Coordinate::new(4, 2)),
+            9
+        );
+    }
+
+    #[test]
+    fn checks_neighbours() {
+        let origin = Coordinate::new(0_usize, 0);
+        assert_eq!(
+            origin.neighbours_4().collect::<Vec<_>>(),
+            vec![Coordinate::new(1, 0), Coordinate::new(0, 1)]
+        );
+        assert_eq!(origin.neighbours_8().count(), 3);
+        assert_eq!(Coordinate::new(5_u8, 5).neighbours_8().count(), 8);
+        assert_eq!(Coordinate::new(u8::MAX, 5).neighbours_4().count(), 3);
+
+        let bounds = Coordinate::new(2, 2);
+        assert_eq!(
+            Coordinate::new(1_usize, 1)
+                .neighbours_8()
+                .filter(|c| c.within(bounds))
+                .count(),
+            3
+        );
+        assert!(!Coordinate::new(-1_i32, 0).within(Coordinate::new(2, 2)));
+    }
+
+    #[test]
+    fn orders_in_reading_order() {
+        let mut coordinates = vec![
+            Coordinate::new(0_i64, 1),
+            Coordinate::new(2, 0),
+            Coordinate::new(1, 0),
+        ];
+        coordinates.sort();
+        assert_eq!(
+            coordinates,
+            vec![
+                Coordinate::new(1, 0),
+                Coordinate::new(2, 0),
+                Coordinate::new(0, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn converts_between_types() {
+        assert_eq!(
+            Coordinate::new(3_usize, 4).convert::<i64>(),
+            Some(Coordinate::new(3_i64, 4))
+        );
+        assert_eq!(Coordinate::new(-3_i64, 4).convert::<usize>(), None);
+    }
+}
@@ -0,0 +1,178 @@
+//! Fast-forwards simulations that end up repeating themselves.
+//!
+//! A simulation that only has finitely many states has to come back to one of them: after
+//! `start` steps it goes around a cycle of `length` states forever. Knowing both, the state
+//! after any number of steps is one of the first `start + length`. [`find_cycle`] finds both
+//! in any sequence, like the values a program computes.
+
+use std::{collections::HashMap, hash::Hash, iter};
+
+/// Where a sequence starts repeating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// The index of the first item that comes back.
+    pub start: usize,
+    /// The number of items it takes to come back.
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The index before the end of the first lap that looks like index `n`.
+    pub fn equivalent(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+
+    /// The number of whole laps between `equivalent(n)` and `n`.
+    pub fn laps(&self, n: usize) -> usize {
+        n.saturating_sub(self.start) / self.length
+    }
+}
+
+/// Take `items` until the key of one repeats, or they run out.
+/// Returns every item taken, including the repeated one, and the cycle if one was found.
+pub fn find_cycle<T, K: Eq + Hash>(
+    items: impl IntoIterator<Item = T>,
+    mut key: impl FnMut(&T) -> K,
+) -> (Vec<T>, Option<Cycle>) {
+    let mut seen = HashMap::new();
+    let mut taken = vec![];
+
+    for (i, item) in items.into_iter().enumerate() {
+        let item_key = key(&item);
+        taken.push(item);
+
+        if let Some(&start) = seen.get(&item_key) {
+            let length = i - start;
+            return (taken, Some(Cycle { start, length }));
+        }
+        seen.insert(item_key, i);
+    }
+
+    (taken, None)
+}
+
+/// Run `step` from `initial` until the key of a state repeats, or `n` steps have run.
+fn run<S, K: Eq + Hash>(
+    initial: S,
+    n: usize,
+    mut step: impl FnMut(&S) -> S,
+    key: impl FnMut(&S) -> K,
+) -> (Vec<S>, Option<Cycle>) {
+    let states = iter::successors(Some(initial), |state| Some(step(state)));
+    find_cycle(states.take(n + 1), key)
+}
+
+/// The state after `n` steps, running no more steps than it takes for a state to repeat.
+pub fn fast_forward<S: Clone + Eq + Hash>(initial: S, n: usize, step: impl FnMut(&S) -> S) -> S {
+    let (mut states, cycle) = run(initial, n, step, S::clone);
+    let index = cycle.map_or(n, |cycle| cycle.equivalent(n));
+    states.swap_remove(index)
+}
+
+/// `value` of the state after `n` steps, for states that repeat up to a translation.
+///
+/// States with the same `key` count as the same shape, like the positions of a pattern
+/// relative to its leftmost one. Once a shape comes back, every lap moves it by the same
+/// amount, so `value` has to change by the same amount every lap too, like a sum of the
+/// positions does. With `key` telling states apart completely, this works for pure cycles.
+pub fn fast_forward_value<S, K: Eq + Hash>(
+    initial: S,
+    n: usize,
+    step: impl FnMut(&S) -> S,
+    key: impl FnMut(&S) -> K,
+    mut value: impl FnMut(&S) -> i64,
+) -> i64 {
+    let (states, cycle) = run(initial, n, step, key);
+    let Some(cycle) = cycle else {
+        return value(&states[n]);
+    };
+
+    let drift = value(&states[cycle.start + cycle.length]) - value(&states[cycle.start]);
+    value(&states[cycle.equivalent(n)]) + cycle.laps(n) as i64 * drift
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{fast_forward, fast_forward_value, find_cycle, Cycle};
+
+    /// 0, 1, 2, then 3, 4, 5, 6 over and over.
+    fn step(x: &u32) -> u32 {
+        if *x == 6 {
+            3
+        } else {
+            x + 1
+        }
+    }
+
+    #[test]
+    fn fast_forwards_cycles() {
+        assert_eq!(fast_forward(0, 2, step), 2);
+        assert_eq!(fast_forward(0, 6, step), 6);
+        assert_eq!(fast_forward(0, 7, step), 3);
+        assert_eq!(
+            fast_forward(0, 1_000_000_000, step),
+            3 + (1_000_000_000 - 3) % 4
+        );
+
+        // fixed points are cycles of one state
+        assert_eq!(fast_forward(5, 1_000_000, |x: &u32| (*x).max(9)), 9);
+    }
+
+    #[test]
+    fn finds_cycles() {
+        let (items, cycle) = find_cycle([5, 0, 3, 0, 3], |x| *x);
+        assert_eq!(items, vec![5, 0, 3, 0]);
+        assert_eq!(
+            cycle,
+            Some(Cycle {
+                start: 1,
+                length: 2
+            })
+        );
+        assert_eq!(cycle.unwrap().equivalent(6), 2);
+        assert_eq!(cycle.unwrap().laps(6), 2);
+
+        assert_eq!(find_cycle(1..4, |x| *x), (vec![1, 2, 3], None));
+    }
+
+    #[test]
+    fn fast_forwards_drifting_values() {
+        // a pattern of two cells that moves right by 3 every 2 steps
+        let glider = |cells: &Vec<i64>| {
+            if cells[1] - cells[0] == 1 {
+                vec![cells[0], cells[1] + 2]
+            } else {
+                vec![cells[0] + 3, cells[0] + 4]
+            }
+        };
+        let shape = |cells: &Vec<i64>| cells[1] - cells[0];
+        let sum = |cells: &Vec<i64>| cells.iter().sum();
+
+        for n in [0, 1, 2, 3, 10, 11] {
+            let mut cells = vec![0, 1];
+            for _ in 0..n {
+                cells = glider(&cells);
+            }
+            assert_eq!(
+                fast_forward_value(vec![0, 1], n, glider, shape, sum),
+                sum(&cells)
+            );
+        }
+        assert_eq!(
+            fast_forward_value(vec![0, 1], 50_000_000_000, glider, shape, sum),
+            2 * 75_000_000_000 + 1
+        );
+
+        // without a drift, the value just cycles
+        assert_eq!(
+            fast_forward_value(0, 1_000_000_000, step, |x| *x, |x| *x as i64),
+            fast_forward(0, 1_000_000_000, step) as i64
+        );
+    }
+}
@@ -0,0 +1,231 @@
+//! Assembles ElfCode written with labels, comments and register names into the plain
+//! format that [`parse_program`](super::parse_program) reads.
+//!
+//! ```text
+//! #ip r4
+//! .alias r0 counter
+//!         seti 0 0 counter
+//! loop:   addi counter 1 counter  ; count up to 5
+//!         eqri counter 5 r1
+//!         addr r1 ip ip           ; skip the jump once done
+//!         jmp loop
+//! ```
+//!
+//! Registers are written as `r3`, as an alias, as `ip` for the register bound to the
+//! instruction pointer, or as a plain number like in the puzzle input. Immediates can be
+//! labels, which stand for the address of the instruction they label. `jmp label` jumps to
+//! the label and `nop` does nothing.
+
+use std::{collections::HashMap, str::FromStr};
+
+use crate::parse::ParseError;
+
+use super::{Opcode, Operand};
+
+/// A line of source that assembles to an instruction, split into its words.
+struct Line<'a> {
+    number: usize,
+    text: &'a str,
+    words: Vec<&'a str>,
+}
+
+impl Line<'_> {
+    /// An error pointing at `word`, which has to be part of the line.
+    fn error(&self, word: &str, expected: impl Into<String>) -> ParseError {
+        let offset = word.as_ptr() as usize - self.text.as_ptr() as usize;
+        ParseError {
+            line: self.number,
+            column: self.text[..offset].chars().count() + 1,
+            text: self.text.to_string(),
+            expected: expected.into(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Symbols {
+    ip_binding: Option<usize>,
+    aliases: HashMap<String, usize>,
+    labels: HashMap<String, usize>,
+}
+
+impl Symbols {
+    fn register(&self, line: &Line, word: &str) -> Result<usize, ParseError> {
+        let register = match word {
+            "ip" => self.ip_binding,
+            _ => word
+                .strip_prefix('r')
+                .unwrap_or(word)
+                .parse()
+                .ok()
+                .or_else(|| self.aliases.get(word).copied()),
+        };
+        register.ok_or_else(|| line.error(word, "a register"))
+    }
+
+    fn immediate(&self, line: &Line, word: &str) -> Result<usize, ParseError> {
+        word.parse()
+            .ok()
+            .or_else(|| self.labels.get(word).copied())
+            .ok_or_else(|| line.error(word, "a number or a label"))
+    }
+}
+
+fn is_name(word: &str) -> bool {
+    word.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && word.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Assemble a program. Errors point at the word that could not be assembled.
+pub fn assemble(source: &str) -> Result<String, ParseError> {
+    let mut symbols = Symbols::default();
+    let mut instructions = vec![];
+    let mut ip_declaration = None;
+
+    // collect labels and aliases first, so that they can be used before they are defined
+    for (number, text) in source.lines().enumerate() {
+        let code = text.split(';').next().unwrap_or_default();
+        let mut words: Vec<&str> = code.split_whitespace().collect();
+        let line = Line {
+            number: number + 1,
+            text,
+            words: vec![],
+        };
+
+        while let Some(label) = words.first().and_then(|w| w.strip_suffix(':')) {
+            if !is_name(label) {
+                return Err(line.error(words[0], "a label made of letters, digits and _"));
+            }
+            if symbols
+                .labels
+                .insert(label.to_string(), instructions.len())
+                .is_some()
+            {
+                return Err(line.error(words[0], "a label that is not defined yet"));
+            }
+            words.remove(0);
+        }
+
+        match words[..] {
+            [] => {}
+            ["#ip", register] => {
+                if ip_declaration.is_some() {
+                    return Err(line.error(words[0], "a single #ip declaration"));
+                }
+                ip_declaration = Some((line, register));
+            }
+            [".alias", register, name] => {
+                if !is_name(name) || name == "ip" || symbols.register(&line, name).is_ok() {
+                    return Err(line.error(name, "an alias that is not a register yet"));
+                }
+                let register = symbols.register(&line, register)?;
+                symbols.aliases.insert(name.to_string(), register);
+            }
+            _ => instructions.push(Line { words, ..line }),
+        }
+    }
+
+    let mut output = String::new();
+    if let Some((line, register)) = ip_declaration {
+        let ip = symbols.register(&line, register)?;
+        symbols.ip_binding = Some(ip);
+        output.push_str(&format!("#ip {ip}\n"));
+    }
+
+    for line in &instructions {
+        let (opcode, operands) = match line.words[..] {
+            ["nop"] => (Opcode::addi, [0, 0, 0]),
+            ["jmp", label] => {
+                let Some(ip) = symbols.ip_binding else {
+                    return Err(line.error(line.words[0], "#ip before jumping"));
+                };
+                // the instruction pointer is incremented after the jump
+                match symbols.immediate(line, label)?.checked_sub(1) {
+                    Some(target) => (Opcode::seti, [target, 0, ip]),
+                    None => return Err(line.error(label, "a target after the first instruction")),
+                }
+            }
+            [mnemonic, a, b, c] => {
+                let Ok(opcode) = Opcode::from_str(mnemonic) else {
+                    return Err(line.error(mnemonic, "an opcode, jmp or nop"));
+                };
+                let input = |word, operand| match operand {
+                    Operand::Register => symbols.register(line, word),
+                    Operand::Immediate | Operand::Ignored => symbols.immediate(line, word),
+                };
+                let [first, second] = opcode.operands();
+                let operands = [
+                    input(a, first)?,
+                    input(b, second)?,
+                    symbols.register(line, c)?,
+                ];
+                (opcode, operands)
+            }
+            _ => return Err(line.error(line.words[0], "an instruction with 3 operands")),
+        };
+
+        let [a, b, c] = operands;
+        output.push_str(&format!("{opcode:?} {a} {b} {c}\n"));
+    }
+
+    Ok(output)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::assemble;
+    use crate::elfcode::{disasm::disassemble, fixtures::COUNT, Machine};
+
+    const SOURCE: &str = "
+#ip r4
+.alias r0 counter
+        seti 0 0 counter
+loop:   addi counter 1 counter  ; count up to 5
+        eqri counter 5 r1
+        addr r1 ip ip           ; skip the jump once done
+        jmp loop
+        mulr ip ip ip           ; jump out of the program
+";
+
+    #[test]
+    fn assembles_programs() {
+        let plain = assemble(SOURCE).unwrap();
+        assert_eq!(plain, COUNT);
+        assert_eq!(assemble("nop\n").unwrap(), "addi 0 0 0\n");
+
+        let mut machine = Machine::<6>::load(&plain).unwrap();
+        machine.run();
+        assert_eq!(machine.registers[0], 5);
+
+        // plain programs assemble to themselves
+        assert_eq!(assemble(&plain).unwrap(), plain);
+    }
+
+    #[test]
+    fn round_trips_through_the_disassembler() {
+        let machine = Machine::<6>::load(&assemble(SOURCE).unwrap()).unwrap();
+        let listing = disassemble(machine.ip_binding(), &machine.program).to_string();
+
+        assert!(listing.contains("L01: ; loop head, back edge from 04"));
+        assert!(listing.contains("04  seti 0 0 4      goto L01"));
+        assert!(listing.contains("03  addr 1 4 4      if r1 goto L05"));
+    }
+
+    #[test]
+    fn points_at_errors() {
+        let error = assemble("#ip 1\nstart: seti 0 0 r0\n  jmp nowhere\n").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 7));
+        assert_eq!(error.expected, "a number or a label");
+
+        let error = assemble("start: jmp start\n").unwrap_err();
+        assert_eq!(error.expected, "#ip before jumping");
+        let error = assemble("#ip 1\nstart: jmp start\n").unwrap_err();
+        assert_eq!(error.expected, "a target after the first instruction");
+        let error = assemble("addx 1 2 3\n").unwrap_err();
+        assert_eq!(error.expected, "an opcode, jmp or nop");
+        let error = assemble(".alias r1 r2\n").unwrap_err();
+        assert_eq!(error.column, 11);
+    }
+}
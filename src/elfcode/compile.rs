@@ -0,0 +1,320 @@
+//! Compiles ElfCode programs into one closure per instruction, so that running them
+//! no longer decodes every instruction on every step.
+//!
+//! Each closure updates the registers and returns the address of the next instruction, or
+//! `None` if it overflows under [`Overflow::Checked`]. Reads of the instruction pointer
+//! register become constants, since the address of every instruction is known, so the
+//! register only has to be written back once the machine stops.
+
+use std::collections::BTreeMap;
+
+use super::{
+    optimizer::Native,
+    word::{Overflow, OverflowError, Word},
+    Instruction, Machine, Opcode, Operand,
+};
+
+type Op<const R: usize, W> = Box<dyn Fn(&mut [W; R]) -> Option<usize>>;
+
+/// An ElfCode program compiled for a machine with `R` registers holding a `W`.
+pub struct Compiled<const R: usize, W: Word = usize> {
+    ops: Vec<Op<R, W>>,
+}
+
+#[derive(Clone, Copy)]
+enum Value<W> {
+    Register(usize),
+    Constant(W),
+}
+
+/// Compile a program, running the `natives` in place of the loops they replace.
+pub fn compile<const R: usize, W: Word>(
+    ip_binding: Option<usize>,
+    program: &[Instruction],
+    natives: &BTreeMap<usize, Native>,
+    overflow: Overflow,
+) -> Compiled<R, W> {
+    let ops = program
+        .iter()
+        .enumerate()
+        .map(|(address, instruction)| {
+            let op = compile_instruction(ip_binding, address, *instruction, overflow);
+            match natives.get(&address) {
+                // loops the native can't stand in for run instruction by instruction
+                Some(native) => {
+                    let native = *native;
+                    Box::new(move |registers: &mut [W; R]| {
+                        native.apply(registers, overflow).or_else(|| op(registers))
+                    })
+                }
+                None => op,
+            }
+        })
+        .collect();
+
+    Compiled { ops }
+}
+
+fn compile_instruction<const R: usize, W: Word>(
+    ip_binding: Option<usize>,
+    address: usize,
+    instruction: Instruction,
+    overflow: Overflow,
+) -> Op<R, W> {
+    let value = |input, operand| match operand {
+        Operand::Register if Some(input) == ip_binding => Value::Constant(W::from_usize(address)),
+        Operand::Register => Value::Register(input),
+        Operand::Immediate | Operand::Ignored => Value::Constant(W::from_usize(input)),
+    };
+    let [first, second] = instruction.opcode.operands();
+    let a = value(instruction.input1, first);
+    let b = value(instruction.input2, second);
+
+    let out = instruction.output;
+    // instructions that write the instruction pointer jump to the address after the value
+    let next = (Some(out) != ip_binding).then_some(address + 1);
+
+    match instruction.opcode {
+        Opcode::addr | Opcode::addi => {
+            specialize(a, b, out, next, overflow, move |x: W, y| x.add(y, overflow))
+        }
+        Opcode::mulr | Opcode::muli => {
+            specialize(a, b, out, next, overflow, move |x: W, y| x.mul(y, overflow))
+        }
+        Opcode::banr | Opcode::bani => {
+            specialize(a, b, out, next, overflow, |x: W, y| Some(x.and(y)))
+        }
+        Opcode::borr | Opcode::bori => {
+            specialize(a, b, out, next, overflow, |x: W, y| Some(x.or(y)))
+        }
+        Opcode::setr | Opcode::seti => specialize(a, b, out, next, overflow, |x, _| Some(x)),
+        Opcode::gtir | Opcode::gtri | Opcode::gtrr => {
+            specialize(a, b, out, next, overflow, |x, y| Some(W::from_bool(x > y)))
+        }
+        Opcode::eqir | Opcode::eqri | Opcode::eqrr => {
+            specialize(a, b, out, next, overflow, |x, y| Some(W::from_bool(x == y)))
+        }
+    }
+}
+
+/// Write `value + 1` to the instruction pointer register like `Machine::try_step` does,
+/// returning the address it jumps to.
+fn jump<W: Word>(
+    registers: &mut [W],
+    register: usize,
+    value: W,
+    overflow: Overflow,
+) -> Option<usize> {
+    let ip = value.add(W::ONE, overflow)?;
+    registers[register] = ip;
+    Some(ip.to_usize().unwrap_or(usize::MAX))
+}
+
+/// Build a closure for one combination of operands, so that none of them is decided at runtime.
+fn specialize<const R: usize, W: Word, F>(
+    a: Value<W>,
+    b: Value<W>,
+    out: usize,
+    next: Option<usize>,
+    overflow: Overflow,
+    f: F,
+) -> Op<R, W>
+where
+    F: Fn(W, W) -> Option<W> + 'static,
+{
+    use Value::*;
+
+    match (a, b, next) {
+        (Register(a), Register(b), Some(next)) => Box::new(move |r| {
+            r[out] = f(r[a], r[b])?;
+            Some(next)
+        }),
+        (Register(a), Register(b), None) => Box::new(move |r| {
+            let value = f(r[a], r[b])?;
+            jump(r, out, value, overflow)
+        }),
+        (Register(a), Constant(b), Some(next)) => Box::new(move |r| {
+            r[out] = f(r[a], b)?;
+            Some(next)
+        }),
+        (Register(a), Constant(b), None) => Box::new(move |r| {
+            let value = f(r[a], b)?;
+            jump(r, out, value, overflow)
+        }),
+        (Constant(a), Register(b), Some(next)) => Box::new(move |r| {
+            r[out] = f(a, r[b])?;
+            Some(next)
+        }),
+        (Constant(a), Register(b), None) => Box::new(move |r| {
+            let value = f(a, r[b])?;
+            jump(r, out, value, overflow)
+        }),
+        (Constant(a), Constant(b), Some(next)) => Box::new(move |r| {
+            r[out] = f(a, b)?;
+            Some(next)
+        }),
+        (Constant(a), Constant(b), None) => Box::new(move |r| {
+            let value = f(a, b)?;
+            jump(r, out, value, overflow)
+        }),
+    }
+}
+
+impl<const R: usize, W: Word> Compiled<R, W> {
+    /// Run `machine` until the instruction pointer gets to `stop`, it halts, `limit`
+    /// instructions have run, or one overflows. The registers and the instruction pointer of
+    /// the machine are left as the interpreter would leave them.
+    fn execute(
+        &self,
+        machine: &mut Machine<R, W>,
+        stop: usize,
+        limit: usize,
+    ) -> Result<(), OverflowError> {
+        let mut ip = machine.ip;
+        let mut executed = 0;
+        let mut overflowed = false;
+
+        while ip != stop && executed < limit {
+            let Some(op) = self.ops.get(ip) else {
+                break;
+            };
+            let Some(next) = op(&mut machine.registers) else {
+                overflowed = true;
+                break;
+            };
+            ip = next;
+            executed += 1;
+        }
+
+        // a jump to an address that doesn't fit a usize already left its value in the register
+        if ip == usize::MAX {
+            machine.ip = ip;
+        } else {
+            machine.set_ip(ip);
+        }
+        machine.executed += executed;
+
+        if overflowed {
+            return Err(OverflowError {
+                address: ip,
+                instruction: machine.program[ip],
+            });
+        }
+        Ok(())
+    }
+
+    /// Run until the machine halts. Programs that never halt make this loop forever.
+    ///
+    /// # Panics
+    /// If an instruction overflows while the machine checks for overflows,
+    /// see [`Compiled::try_run`].
+    pub fn run(&self, machine: &mut Machine<R, W>) {
+        self.try_run(machine).unwrap_or_else(|e| panic!("{e}"));
+    }
+
+    /// Like [`Compiled::run`], but stops at the first instruction that overflows, like
+    /// [`Machine::try_run`].
+    pub fn try_run(&self, machine: &mut Machine<R, W>) -> Result<(), OverflowError> {
+        self.execute(machine, usize::MAX, usize::MAX)
+    }
+
+    /// Run until the instruction pointer gets to `address`.
+    /// Returns false if the machine halted first.
+    ///
+    /// # Panics
+    /// If an instruction overflows while the machine checks for overflows.
+    pub fn run_to(&self, machine: &mut Machine<R, W>, address: usize) -> bool {
+        self.execute(machine, address, usize::MAX)
+            .unwrap_or_else(|e| panic!("{e}"));
+        machine.ip == address
+    }
+
+    /// Run at most `limit` instructions.
+    /// Returns the number of instructions executed if the machine halted within the limit.
+    ///
+    /// # Panics
+    /// If an instruction overflows while the machine checks for overflows.
+    pub fn run_with_limit(&self, machine: &mut Machine<R, W>, limit: usize) -> Option<usize> {
+        let start = machine.executed;
+        self.execute(machine, usize::MAX, limit)
+            .unwrap_or_else(|e| panic!("{e}"));
+        machine.is_halted().then_some(machine.executed - start)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use crate::elfcode::{
+        fixtures::{COUNT, DIVISOR_SUM, OVERFLOW},
+        word::Overflow,
+        Instruction, Machine, Opcode,
+    };
+
+    #[test]
+    fn runs_like_the_interpreter() {
+        for (program, optimize) in [(COUNT, false), (DIVISOR_SUM, false), (DIVISOR_SUM, true)] {
+            let mut interpreted = Machine::<6>::load(program).unwrap();
+            if optimize {
+                interpreted.optimize();
+            }
+            let mut compiled = interpreted.clone();
+
+            interpreted.run();
+            compiled.compile().run(&mut compiled);
+            assert!(compiled.is_halted());
+            assert_eq!(compiled.registers, interpreted.registers);
+            assert_eq!(compiled.executed(), interpreted.executed());
+        }
+    }
+
+    #[test]
+    fn follows_the_word_size_and_overflow_policy() {
+        for overflow in [Overflow::Wrapping, Overflow::Saturating, Overflow::Checked] {
+            let mut interpreted = Machine::<6, i32>::load(OVERFLOW)
+                .unwrap()
+                .with_overflow(overflow);
+            let mut compiled = interpreted.clone();
+
+            let result = interpreted.try_run();
+            assert_eq!(compiled.compile().try_run(&mut compiled), result);
+            assert_eq!(compiled.registers, interpreted.registers);
+            assert_eq!(compiled.ip(), interpreted.ip());
+            assert_eq!(compiled.executed(), interpreted.executed());
+        }
+
+        let mut machine = Machine::<6, i32>::load(OVERFLOW)
+            .unwrap()
+            .with_overflow(Overflow::Checked);
+        let error = machine.compile().try_run(&mut machine).unwrap_err();
+        assert_eq!(error.to_string(), "instruction 01 overflowed: muli 0 3 0");
+        // 3^20 is the first power of 3 that doesn't fit
+        assert_eq!(machine.registers[0], 3i32.pow(19));
+
+        // jumping to a negative address halts, leaving the register as it is
+        let subtract = Instruction::new(Opcode::addi, 0, -3i64 as usize, 0);
+        let mut interpreted = Machine::<2, i64>::new(Some(0), vec![subtract]);
+        let mut compiled = interpreted.clone();
+        interpreted.run();
+        compiled.compile().run(&mut compiled);
+        assert!(compiled.is_halted());
+        assert_eq!(compiled.registers, interpreted.registers);
+        assert_eq!(compiled.registers[0], -2);
+    }
+
+    #[test]
+    fn stops_at_addresses_and_limits() {
+        let mut machine = Machine::<6>::load(COUNT).unwrap();
+        let compiled = machine.compile();
+
+        assert!(compiled.run_to(&mut machine, 3));
+        assert_eq!(machine.registers, [1, 0, 0, 0, 3, 0]);
+        // already there
+        assert!(compiled.run_to(&mut machine, 3));
+        assert_eq!(compiled.run_with_limit(&mut machine, 2), None);
+        assert_eq!(machine.to_string(), "ip=1 [1, 0, 0, 0, 1, 0]");
+        assert_eq!(compiled.run_with_limit(&mut machine, 100), Some(16));
+        assert!(!compiled.run_to(&mut machine, 3));
+    }
+}
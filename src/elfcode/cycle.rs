@@ -0,0 +1,113 @@
+//! Watches the registers every time a program gets to an instruction, until what it sees
+//! repeats.
+//!
+//! Programs like day 21 generate a sequence of values and compare each one with register 0,
+//! halting on a match. Once the sequence repeats, every value that can halt the program has
+//! been seen: the first one halts it soonest, and the last new one latest.
+
+use std::{hash::Hash, iter};
+
+use super::{word::Word, Machine};
+use crate::cycle::{self, Cycle};
+
+/// The values seen at an instruction, up to the first one that repeats.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sequence<K> {
+    /// Every value, in the order it was first seen.
+    pub values: Vec<K>,
+    /// Where the values start repeating.
+    pub cycle: Cycle,
+}
+
+impl<K> Sequence<K> {
+    pub fn first(&self) -> &K {
+        &self.values[0]
+    }
+
+    /// The last value seen before the sequence repeats.
+    pub fn last_new(&self) -> &K {
+        &self.values[self.values.len() - 1]
+    }
+}
+
+/// Run `machine` and compute `key` from the registers every time it gets to `address`,
+/// until a key comes up again. The machine is left at `address` with the repeated key in its
+/// registers, or `None` is returned if it halts first.
+///
+/// `key` picks what has to repeat: `|r| r[5]` for the value in a register, or `|r| *r` for
+/// the whole state. Programs that keep producing new keys make this loop forever.
+pub fn find_cycle<const R: usize, W: Word, K: Clone + Eq + Hash>(
+    machine: &mut Machine<R, W>,
+    address: usize,
+    mut key: impl FnMut(&[W; R]) -> K,
+) -> Option<Sequence<K>> {
+    // step past the instruction before looking for it again, except the first time
+    let mut started = false;
+    let keys = iter::from_fn(|| {
+        if started {
+            machine.step();
+        }
+        started = true;
+        machine
+            .run_until(|m| m.ip() == address)
+            .then(|| key(&machine.registers))
+    });
+
+    let (mut values, cycle) = cycle::find_cycle(keys, K::clone);
+    // the repeated value is already in there
+    values.pop();
+    cycle.map(|cycle| Sequence { values, cycle })
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{find_cycle, Sequence};
+    use crate::{cycle::Cycle, elfcode::Machine};
+
+    #[test]
+    fn finds_cycles() {
+        // r1 = (r1 + 3) & 7 forever, starting from 5
+        const COUNTER: &str = "#ip 2\nseti 5 0 1\naddi 1 3 1\nbani 1 7 1\nseti 0 0 2\n";
+
+        let mut machine = Machine::<4>::load(COUNTER).unwrap();
+        let sequence = find_cycle(&mut machine, 1, |r| r[1]).unwrap();
+        assert_eq!(sequence.values, vec![5, 0, 3, 6, 1, 4, 7, 2]);
+        assert_eq!((sequence.first(), sequence.last_new()), (&5, &2));
+        assert_eq!(
+            sequence.cycle,
+            Cycle {
+                start: 0,
+                length: 8
+            }
+        );
+        // stopped at the repeat
+        assert_eq!((machine.ip(), machine.registers[1]), (1, 5));
+
+        let mut machine = Machine::<4>::load(COUNTER).unwrap();
+        let sequence = find_cycle(&mut machine, 1, |r| *r).unwrap();
+        assert_eq!(sequence.cycle.length, 8);
+    }
+
+    #[test]
+    fn finds_cycles_that_start_later() {
+        // r1 = (r1 * r1 + 1) & 7 forever, starting from 1
+        let program = "#ip 3\nseti 1 0 1\nmulr 1 1 1\naddi 1 1 1\nbani 1 7 1\nseti 0 0 3\n";
+        let mut machine = Machine::<4, u32>::load(program).unwrap();
+        assert_eq!(
+            find_cycle(&mut machine, 1, |r| r[1]),
+            Some(Sequence {
+                values: vec![1, 2, 5],
+                cycle: Cycle {
+                    start: 1,
+                    length: 2
+                }
+            })
+        );
+
+        let mut machine = Machine::<4>::load("seti 1 0 1\naddi 1 1 1\n").unwrap();
+        assert_eq!(find_cycle(&mut machine, 1, |r| r[1]), None);
+        assert!(machine.is_halted());
+    }
+}
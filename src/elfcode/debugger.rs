@@ -0,0 +1,382 @@
+//! An interactive debugger for ElfCode machines.
+
+use std::{
+    collections::BTreeSet,
+    fs::File,
+    io::{self, BufRead, BufWriter, Write},
+    str::FromStr,
+};
+
+use super::{
+    disasm::{disassemble, Listing},
+    Machine,
+};
+
+/// Stop when a register changes, or when it changes to a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Watchpoint {
+    Changes(usize),
+    Equals(usize, usize),
+}
+
+impl Watchpoint {
+    fn triggers(&self, before: &[usize], after: &[usize]) -> bool {
+        match *self {
+            Watchpoint::Changes(r) => before[r] != after[r],
+            Watchpoint::Equals(r, value) => before[r] != value && after[r] == value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Step(usize),
+    Continue,
+    Break(usize),
+    Delete(usize),
+    Watch(Watchpoint),
+    Unwatch,
+    Registers,
+    Set(usize, usize),
+    Trace(Option<String>),
+    Help,
+    Quit,
+}
+
+fn register(s: &str) -> Result<usize, String> {
+    s.strip_prefix('r')
+        .and_then(|r| r.parse().ok())
+        .ok_or_else(|| format!("expected a register like r3, found \"{s}\""))
+}
+
+fn number(s: &str) -> Result<usize, String> {
+    s.parse()
+        .map_err(|_| format!("expected a number, found \"{s}\""))
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        match words[..] {
+            ["s" | "step"] => Ok(Command::Step(1)),
+            ["s" | "step", n] => Ok(Command::Step(number(n)?)),
+            ["c" | "continue"] => Ok(Command::Continue),
+            ["b" | "break", address] => Ok(Command::Break(number(address)?)),
+            ["d" | "delete", address] => Ok(Command::Delete(number(address)?)),
+            ["w" | "watch", r] => Ok(Command::Watch(Watchpoint::Changes(register(r)?))),
+            ["w" | "watch", r, "==", value] => Ok(Command::Watch(Watchpoint::Equals(
+                register(r)?,
+                number(value)?,
+            ))),
+            ["unwatch"] => Ok(Command::Unwatch),
+            ["r" | "regs"] => Ok(Command::Registers),
+            ["set", r, value] => Ok(Command::Set(register(r)?, number(value)?)),
+            ["trace", "off"] => Ok(Command::Trace(None)),
+            ["trace", path] => Ok(Command::Trace(Some(path.to_string()))),
+            ["h" | "help"] => Ok(Command::Help),
+            ["q" | "quit"] => Ok(Command::Quit),
+            _ => Err(format!("unknown command \"{s}\", try \"help\"")),
+        }
+    }
+}
+
+const HELP: &str = "\
+s, step [n]        execute one or n instructions
+c, continue        run until a breakpoint, a watchpoint or the end of the program
+b, break N         stop before instruction N
+d, delete N        remove the breakpoint at instruction N
+w, watch rN        stop when register N changes
+w, watch rN == V   stop when register N becomes V
+unwatch            remove all watchpoints
+r, regs            show the registers and the next instruction
+set rN V           set register N to V
+trace FILE         write every executed instruction to FILE, `trace off` to stop
+q, quit            leave the debugger";
+
+/// Why the machine stopped running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    Stepped,
+    Breakpoint(usize),
+    Watchpoint(Watchpoint),
+    Halted,
+}
+
+pub struct Debugger<const R: usize> {
+    pub machine: Machine<R>,
+    listing: Listing,
+    breakpoints: BTreeSet<usize>,
+    watchpoints: Vec<Watchpoint>,
+    trace: Option<BufWriter<File>>,
+}
+
+impl<const R: usize> Debugger<R> {
+    pub fn new(machine: Machine<R>) -> Self {
+        let listing = disassemble(machine.ip_binding(), &machine.program);
+        Debugger {
+            machine,
+            listing,
+            breakpoints: BTreeSet::new(),
+            watchpoints: vec![],
+            trace: None,
+        }
+    }
+
+    /// Execute one instruction, checking watchpoints and writing the trace.
+    fn step(&mut self) -> io::Result<Option<Stop>> {
+        let before = self.machine.registers;
+        let ip = self.machine.ip();
+        let Some(instruction) = self.machine.current() else {
+            return Ok(Some(Stop::Halted));
+        };
+        self.machine.step();
+
+        if let Some(trace) = &mut self.trace {
+            writeln!(
+                trace,
+                "{:>10}  {ip:02}  {:<16}{:?} -> {:?}",
+                self.machine.executed(),
+                instruction.to_string(),
+                before,
+                self.machine.registers
+            )?;
+        }
+
+        Ok(self
+            .watchpoints
+            .iter()
+            .find(|w| w.triggers(&before, &self.machine.registers))
+            .map(|w| Stop::Watchpoint(*w)))
+    }
+
+    /// Run `count` instructions, or until something stops the machine if `count` is `None`.
+    pub fn resume(&mut self, count: Option<usize>) -> io::Result<Stop> {
+        let mut executed = 0;
+        loop {
+            if let Some(stop) = self.step()? {
+                return Ok(stop);
+            }
+            executed += 1;
+
+            if self.machine.is_halted() {
+                return Ok(Stop::Halted);
+            }
+            if self.breakpoints.contains(&self.machine.ip()) {
+                return Ok(Stop::Breakpoint(self.machine.ip()));
+            }
+            if count.is_some_and(|count| executed >= count) {
+                return Ok(Stop::Stepped);
+            }
+        }
+    }
+
+    fn location(&self) -> String {
+        let ip = self.machine.ip();
+        match self.machine.current() {
+            Some(instruction) => format!(
+                "{ip:02}  {:<16}{}",
+                instruction.to_string(),
+                self.listing.code(ip).unwrap_or_default()
+            ),
+            None => format!("{ip:02}  halted"),
+        }
+    }
+
+    /// Run a single command. Returns false once the user wants to quit.
+    pub fn execute(&mut self, command: Command, out: &mut impl Write) -> io::Result<bool> {
+        match command {
+            Command::Step(count) => {
+                let stop = self.resume(Some(count.max(1)))?;
+                self.report(stop, out)?;
+            }
+            Command::Continue => {
+                let stop = self.resume(None)?;
+                self.report(stop, out)?;
+            }
+            Command::Break(address) => {
+                self.breakpoints.insert(address);
+                writeln!(out, "breakpoint at {address:02}")?;
+            }
+            Command::Delete(address) => {
+                if !self.breakpoints.remove(&address) {
+                    writeln!(out, "no breakpoint at {address:02}")?;
+                }
+            }
+            Command::Watch(watchpoint) => {
+                if watchpoint_register(watchpoint) >= R {
+                    writeln!(out, "the machine only has {R} registers")?;
+                } else {
+                    self.watchpoints.push(watchpoint);
+                }
+            }
+            Command::Unwatch => self.watchpoints.clear(),
+            Command::Registers => {
+                writeln!(out, "{}", self.machine)?;
+                writeln!(out, "{}", self.location())?;
+            }
+            Command::Set(register, _) if register >= R => {
+                writeln!(out, "the machine only has {R} registers")?
+            }
+            // like in the program, writing the bound register moves the instruction pointer
+            Command::Set(register, value) if Some(register) == self.machine.ip_binding() => {
+                self.machine.set_ip(value)
+            }
+            Command::Set(register, value) => self.machine.registers[register] = value,
+            Command::Trace(path) => {
+                if let Some(mut trace) = self.trace.take() {
+                    trace.flush()?;
+                }
+                if let Some(path) = path {
+                    self.trace = Some(BufWriter::new(File::create(&path)?));
+                    writeln!(out, "tracing to {path}")?;
+                }
+            }
+            Command::Help => writeln!(out, "{HELP}")?,
+            Command::Quit => {
+                if let Some(mut trace) = self.trace.take() {
+                    trace.flush()?;
+                }
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn report(&self, stop: Stop, out: &mut impl Write) -> io::Result<()> {
+        match stop {
+            Stop::Stepped => {}
+            Stop::Breakpoint(address) => writeln!(out, "breakpoint at {address:02}")?,
+            Stop::Watchpoint(Watchpoint::Changes(r)) => writeln!(out, "r{r} changed")?,
+            Stop::Watchpoint(Watchpoint::Equals(r, value)) => writeln!(out, "r{r} is {value}")?,
+            Stop::Halted => writeln!(out, "halted after {} instructions", self.machine.executed())?,
+        }
+        writeln!(out, "{}", self.machine)?;
+        writeln!(out, "{}", self.location())
+    }
+
+    /// Read commands from `input` until the user quits or the input ends.
+    pub fn repl(&mut self, input: impl BufRead, out: &mut impl Write) -> io::Result<()> {
+        writeln!(out, "{}", self.location())?;
+        write!(out, "(elfcode) ")?;
+        out.flush()?;
+
+        for line in input.lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                match line.parse::<Command>() {
+                    Ok(command) => {
+                        if !self.execute(command, out)? {
+                            return Ok(());
+                        }
+                    }
+                    Err(e) => writeln!(out, "{e}")?,
+                }
+            }
+            write!(out, "(elfcode) ")?;
+            out.flush()?;
+        }
+
+        writeln!(out)
+    }
+}
+
+fn watchpoint_register(watchpoint: Watchpoint) -> usize {
+    match watchpoint {
+        Watchpoint::Changes(r) | Watchpoint::Equals(r, _) => r,
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Command, Debugger, Stop, Watchpoint};
+    use crate::elfcode::{fixtures::COUNT, Machine};
+
+    fn get_debugger() -> Debugger<6> {
+        Debugger::new(Machine::load(COUNT).unwrap())
+    }
+
+    #[test]
+    fn parses_commands() {
+        assert_eq!("s".parse(), Ok(Command::Step(1)));
+        assert_eq!("step 10".parse(), Ok(Command::Step(10)));
+        assert_eq!("b 3".parse(), Ok(Command::Break(3)));
+        assert_eq!(
+            "watch r0 == 3".parse(),
+            Ok(Command::Watch(Watchpoint::Equals(0, 3)))
+        );
+        assert_eq!("set r2 7".parse(), Ok(Command::Set(2, 7)));
+        assert_eq!("trace off".parse(), Ok(Command::Trace(None)));
+        assert!("watch 0".parse::<Command>().is_err());
+        assert!("jump".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn stops_at_breakpoints_and_watchpoints() {
+        let mut debugger = get_debugger();
+        let mut out = vec![];
+
+        debugger.execute(Command::Break(3), &mut out).unwrap();
+        assert_eq!(debugger.resume(None).unwrap(), Stop::Breakpoint(3));
+        assert_eq!(debugger.machine.registers[0], 1);
+        assert_eq!(debugger.resume(None).unwrap(), Stop::Breakpoint(3));
+        assert_eq!(debugger.machine.registers[0], 2);
+
+        debugger.execute(Command::Delete(3), &mut out).unwrap();
+        debugger
+            .execute(Command::Watch(Watchpoint::Equals(0, 4)), &mut out)
+            .unwrap();
+        assert_eq!(
+            debugger.resume(None).unwrap(),
+            Stop::Watchpoint(Watchpoint::Equals(0, 4))
+        );
+
+        debugger.execute(Command::Unwatch, &mut out).unwrap();
+        debugger.execute(Command::Set(0, 5), &mut out).unwrap();
+        assert_eq!(debugger.resume(Some(2)).unwrap(), Stop::Stepped);
+        assert_eq!(debugger.resume(None).unwrap(), Stop::Halted);
+        assert!(debugger.machine.is_halted());
+    }
+
+    #[test]
+    fn jumps_when_setting_the_ip_register() {
+        let mut debugger = get_debugger();
+        let mut out = vec![];
+
+        debugger.execute(Command::Set(4, 5), &mut out).unwrap();
+        assert_eq!(debugger.machine.to_string(), "ip=5 [0, 0, 0, 0, 5, 0]");
+        assert_eq!(
+            debugger.machine.current().unwrap().to_string(),
+            "mulr 4 4 4"
+        );
+
+        // `mulr 4 4 4` jumps past the end of the program
+        debugger.execute(Command::Step(1), &mut out).unwrap();
+        assert!(debugger.machine.is_halted());
+        assert_eq!(debugger.machine.registers[4], 26);
+
+        debugger.execute(Command::Set(6, 1), &mut out).unwrap();
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .contains("only has 6 registers"));
+    }
+
+    #[test]
+    fn runs_a_session() {
+        let mut debugger = get_debugger();
+        let mut out = vec![];
+        debugger
+            .repl("s 2\nregs\nnonsense\nc\nq\n".as_bytes(), &mut out)
+            .unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        assert!(out.contains("ip=2 [1, 0, 0, 0, 2, 0]"));
+        assert!(out.contains("02  eqri 0 5 1      r1 = r0 == 5"));
+        assert!(out.contains("unknown command \"nonsense\""));
+        assert!(out.contains("halted after"));
+    }
+}
@@ -0,0 +1,426 @@
+//! Turns ElfCode programs into annotated pseudocode, so they can be read instead of reverse engineered.
+
+use std::{collections::BTreeSet, fmt, ops::Range};
+
+use super::{execute, Instruction, Operand};
+
+/// Where control goes after an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    /// Continue with the next instruction.
+    Next,
+    /// Jump to a fixed address. Addresses past the end of the program halt the machine.
+    Goto(usize),
+    /// Skip the next instruction if the register holds 1, like a comparison writes it.
+    SkipIf(usize),
+    /// Jump to an address that is only known at runtime.
+    Computed,
+}
+
+/// Work out where control goes after the instruction at `address`.
+pub fn flow(ip_binding: Option<usize>, address: usize, instruction: Instruction) -> Flow {
+    let Some(ip) = ip_binding.filter(|ip| *ip == instruction.output) else {
+        return Flow::Next;
+    };
+
+    let others: Vec<usize> = instruction.reads().filter(|r| *r != ip).collect();
+    let reads_ip = instruction.reads().any(|r| r == ip);
+
+    match others[..] {
+        // every input is known, so evaluate the instruction with the address in the ip register.
+        [] if instruction
+            .reads()
+            .chain([ip])
+            .all(|r| r < CONSTANT_REGISTERS) =>
+        {
+            let mut registers = [0; CONSTANT_REGISTERS];
+            registers[ip] = address;
+            // the pointer wraps around like it does on the machine.
+            Flow::Goto(execute(registers, instruction)[ip].wrapping_add(1))
+        }
+        [register] if reads_ip && instruction.opcode.operator() == Some("+") => {
+            Flow::SkipIf(register)
+        }
+        _ => Flow::Computed,
+    }
+}
+
+/// Enough registers to constant-fold instructions of every ElfCode machine in the puzzles.
+const CONSTANT_REGISTERS: usize = 16;
+
+/// A loop, recovered from the jumps back to an earlier address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Loop {
+    pub head: usize,
+    /// The last instruction of the loop.
+    pub tail: usize,
+    /// The addresses of all jumps back to `head`.
+    pub back_edges: Vec<usize>,
+}
+
+#[derive(Debug)]
+struct Line {
+    instruction: Instruction,
+    code: String,
+    notes: Vec<String>,
+}
+
+/// A disassembled program, formatted as annotated pseudocode by its `Display` implementation.
+#[derive(Debug)]
+pub struct Listing {
+    ip_binding: Option<usize>,
+    lines: Vec<Line>,
+    targets: BTreeSet<usize>,
+    leaders: BTreeSet<usize>,
+    loops: Vec<Loop>,
+}
+
+impl Listing {
+    /// The basic blocks of the program, in order.
+    pub fn blocks(&self) -> Vec<Range<usize>> {
+        let ends = self
+            .leaders
+            .iter()
+            .skip(1)
+            .copied()
+            .chain([self.lines.len()]);
+        self.leaders
+            .iter()
+            .zip(ends)
+            .map(|(&start, end)| start..end)
+            .collect()
+    }
+
+    pub fn loops(&self) -> &[Loop] {
+        &self.loops
+    }
+
+    /// The pseudocode of a single instruction.
+    pub fn code(&self, address: usize) -> Option<&str> {
+        self.lines.get(address).map(|line| line.code.as_str())
+    }
+
+    /// The addresses that are the target of a jump.
+    pub fn targets(&self) -> impl Iterator<Item = usize> + '_ {
+        self.targets.iter().copied()
+    }
+
+    fn depth(&self, address: usize) -> usize {
+        self.loops
+            .iter()
+            .filter(|l| (l.head..=l.tail).contains(&address))
+            .count()
+    }
+}
+
+/// Disassemble a program as returned by [`parse_program`](super::parse_program).
+pub fn disassemble(ip_binding: Option<usize>, program: &[Instruction]) -> Listing {
+    let flows: Vec<Flow> = program
+        .iter()
+        .enumerate()
+        .map(|(address, instruction)| flow(ip_binding, address, *instruction))
+        .collect();
+
+    let mut targets = BTreeSet::new();
+    let mut leaders = BTreeSet::from([0]);
+    let mut loops = vec![];
+
+    for (address, flow) in flows.iter().enumerate() {
+        match *flow {
+            Flow::Next => continue,
+            Flow::Goto(target) => {
+                targets.insert(target);
+                if target <= address {
+                    match loops.iter_mut().find(|l: &&mut Loop| l.head == target) {
+                        Some(l) => {
+                            l.tail = address;
+                            l.back_edges.push(address);
+                        }
+                        None => loops.push(Loop {
+                            head: target,
+                            tail: address,
+                            back_edges: vec![address],
+                        }),
+                    }
+                }
+            }
+            Flow::SkipIf(_) => {
+                targets.extend([address + 1, address + 2]);
+            }
+            Flow::Computed => {}
+        }
+        leaders.insert(address + 1);
+    }
+
+    loops.sort_by_key(|l| l.head);
+    targets.retain(|t| *t < program.len());
+    leaders.extend(&targets);
+    leaders.retain(|l| *l < program.len());
+
+    let name = |register: usize| match ip_binding {
+        Some(ip) if ip == register => "ip".to_string(),
+        _ => format!("r{register}"),
+    };
+    let label = |target: usize| {
+        if target < program.len() {
+            format!("L{target:02}")
+        } else {
+            "end".to_string()
+        }
+    };
+
+    let lines = program
+        .iter()
+        .enumerate()
+        .map(|(address, &instruction)| {
+            let code = match flows[address] {
+                Flow::Next => assignment(instruction, address, ip_binding, &name),
+                Flow::Goto(target) if target < program.len() => format!("goto {}", label(target)),
+                Flow::Goto(_) => "halt".to_string(),
+                Flow::SkipIf(register) => {
+                    format!("if {} goto {}", name(register), label(address + 2))
+                }
+                Flow::Computed => format!(
+                    "goto {} + 1",
+                    expression(instruction, address, ip_binding, &name)
+                ),
+            };
+
+            let mut notes = vec![];
+            if let Some(Flow::SkipIf(register)) = flows.get(address + 1) {
+                if instruction.opcode.is_comparison() && instruction.output == *register {
+                    notes.push(format!("condition of the jump at {:02}", address + 1));
+                }
+            }
+            if instruction.reads().any(|r| Some(r) == ip_binding) && flows[address] == Flow::Next {
+                notes.push(format!("ip is {address} here"));
+            }
+
+            Line {
+                instruction,
+                code,
+                notes,
+            }
+        })
+        .collect();
+
+    Listing {
+        ip_binding,
+        lines,
+        targets,
+        leaders,
+        loops,
+    }
+}
+
+/// An input of the instruction as pseudocode. Reads of the instruction pointer become the current address.
+fn operand(
+    operand: Operand,
+    value: usize,
+    address: usize,
+    ip_binding: Option<usize>,
+    name: &impl Fn(usize) -> String,
+) -> String {
+    match operand {
+        Operand::Register if Some(value) == ip_binding => address.to_string(),
+        Operand::Register => name(value),
+        Operand::Immediate | Operand::Ignored => value.to_string(),
+    }
+}
+
+fn expression(
+    instruction: Instruction,
+    address: usize,
+    ip_binding: Option<usize>,
+    name: &impl Fn(usize) -> String,
+) -> String {
+    let [first, second] = instruction.opcode.operands();
+    let a = operand(first, instruction.input1, address, ip_binding, name);
+    let b = operand(second, instruction.input2, address, ip_binding, name);
+
+    match instruction.opcode.operator() {
+        Some(operator) => format!("{a} {operator} {b}"),
+        None => a,
+    }
+}
+
+fn assignment(
+    instruction: Instruction,
+    address: usize,
+    ip_binding: Option<usize>,
+    name: &impl Fn(usize) -> String,
+) -> String {
+    let output = name(instruction.output);
+    let [first, second] = instruction.opcode.operands();
+    let is_output = |operand, value| {
+        operand == Operand::Register && value == instruction.output && Some(value) != ip_binding
+    };
+
+    match instruction.opcode.operator() {
+        Some(operator) if !instruction.opcode.is_comparison() => {
+            // every arithmetic operator is commutative, so `r0 = r1 + r0` becomes `r0 += r1` too.
+            let other = if is_output(first, instruction.input1) {
+                Some(operand(
+                    second,
+                    instruction.input2,
+                    address,
+                    ip_binding,
+                    name,
+                ))
+            } else if is_output(second, instruction.input2) {
+                Some(operand(
+                    first,
+                    instruction.input1,
+                    address,
+                    ip_binding,
+                    name,
+                ))
+            } else {
+                None
+            };
+            match other {
+                Some(other) => format!("{output} {operator}= {other}"),
+                None => format!(
+                    "{output} = {}",
+                    expression(instruction, address, ip_binding, name)
+                ),
+            }
+        }
+        _ => format!(
+            "{output} = {}",
+            expression(instruction, address, ip_binding, name)
+        ),
+    }
+}
+
+impl fmt::Display for Listing {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(ip) = self.ip_binding {
+            writeln!(
+                f,
+                "; #ip {ip}: r{ip} is the instruction pointer, shown as `ip`"
+            )?;
+        }
+        writeln!(
+            f,
+            "; {} instructions in {} blocks, {} loops",
+            self.lines.len(),
+            self.leaders.len(),
+            self.loops.len()
+        )?;
+
+        for (address, line) in self.lines.iter().enumerate() {
+            if self.leaders.contains(&address) {
+                writeln!(f)?;
+            }
+            if self.targets.contains(&address) {
+                let tails: Vec<String> = self
+                    .loops
+                    .iter()
+                    .filter(|l| l.head == address)
+                    .flat_map(|l| &l.back_edges)
+                    .map(|tail| format!("{tail:02}"))
+                    .collect();
+                if tails.is_empty() {
+                    writeln!(f, "L{address:02}:")?;
+                } else {
+                    writeln!(
+                        f,
+                        "L{address:02}: ; loop head, back edge from {}",
+                        tails.join(", ")
+                    )?;
+                }
+            }
+
+            let indent = "    ".repeat(self.depth(address) + 1);
+            let code = format!(
+                "{indent}{address:02}  {:<16}{}",
+                line.instruction.to_string(),
+                line.code
+            );
+            if line.notes.is_empty() {
+                writeln!(f, "{code}")?;
+            } else {
+                writeln!(f, "{code:<48} ; {}", line.notes.join("; "))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{disassemble, flow, Flow, Loop};
+    use crate::elfcode::{fixtures::COUNT, parse_program, Instruction, Opcode};
+
+    #[test]
+    fn classifies_jumps() {
+        let ip = Some(3);
+        assert_eq!(
+            flow(ip, 4, Instruction::new(Opcode::addi, 1, 2, 1)),
+            Flow::Next
+        );
+        assert_eq!(
+            flow(ip, 4, Instruction::new(Opcode::addi, 3, 2, 3)),
+            Flow::Goto(7)
+        );
+        assert_eq!(
+            flow(ip, 4, Instruction::new(Opcode::seti, 0, 9, 3)),
+            Flow::Goto(1)
+        );
+        assert_eq!(
+            flow(ip, 4, Instruction::new(Opcode::mulr, 3, 3, 3)),
+            Flow::Goto(17)
+        );
+        assert_eq!(
+            flow(ip, 4, Instruction::new(Opcode::seti, usize::MAX, 0, 3)),
+            Flow::Goto(0)
+        );
+        assert_eq!(
+            flow(ip, 4, Instruction::new(Opcode::addr, 2, 3, 3)),
+            Flow::SkipIf(2)
+        );
+        assert_eq!(
+            flow(ip, 4, Instruction::new(Opcode::setr, 2, 0, 3)),
+            Flow::Computed
+        );
+        assert_eq!(
+            flow(None, 4, Instruction::new(Opcode::seti, 0, 9, 3)),
+            Flow::Next
+        );
+    }
+
+    #[test]
+    fn recovers_blocks_and_loops() {
+        let (ip, program) = parse_program(COUNT).unwrap().1;
+        let listing = disassemble(ip, &program);
+
+        assert_eq!(listing.blocks(), vec![0..1, 1..4, 4..5, 5..6]);
+        assert_eq!(
+            listing.loops(),
+            &[Loop {
+                head: 1,
+                tail: 4,
+                back_edges: vec![4]
+            }]
+        );
+        assert_eq!(listing.targets().collect::<Vec<_>>(), vec![1, 4, 5]);
+
+        let text = listing.to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines[0],
+            "; #ip 4: r4 is the instruction pointer, shown as `ip`"
+        );
+        assert_eq!(lines[1], "; 6 instructions in 4 blocks, 1 loops");
+        assert!(text.contains("L01: ; loop head, back edge from 04"));
+        assert!(text.contains("        01  addi 0 1 0      r0 += 1"));
+        assert!(text.contains("r1 = r0 == 5") && text.contains("; condition of the jump at 03"));
+        assert!(text.contains("03  addr 1 4 4      if r1 goto L05"));
+        assert!(text.contains("04  seti 0 0 4      goto L01"));
+        assert!(text.contains("05  mulr 4 4 4      halt"));
+    }
+}
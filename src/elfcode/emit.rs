@@ -0,0 +1,93 @@
+//! Turns ElfCode programs into standalone Rust functions. Its tests are in `native`, where
+//! they can run what it emits.
+
+use std::fmt::Write;
+
+use super::opcode::{Opcode, Operand};
+
+/// Emit `pub fn {name}<const R: usize>(registers: &mut [usize; R]) -> usize`, which runs the
+/// program from its first instruction until it halts and returns the number of instructions
+/// executed. Like `Machine::run`, it loops forever for programs that never halt, and its
+/// arithmetic wraps like a machine with the default `Overflow::Wrapping`.
+///
+/// `source` is read line by line instead of with `parse_program`, which needs `nom`. Lines are
+/// trimmed and blank ones skipped, so this accepts a little more than the machine does.
+pub fn emit(name: &str, source: &str) -> Result<String, String> {
+    let mut lines = source.lines().map(str::trim).filter(|l| !l.is_empty());
+    let mut ip_binding = None;
+    let mut body = String::new();
+
+    let mut first = lines.next();
+    if let Some(declaration) = first.and_then(|l| l.strip_prefix("#ip ")) {
+        ip_binding = Some(number(declaration)?);
+        first = lines.next();
+    }
+
+    for (address, line) in first.into_iter().chain(lines).enumerate() {
+        let [opcode, a, b, c] = line.split(' ').collect::<Vec<_>>()[..] else {
+            return Err(format!("expected an instruction, found \"{line}\""));
+        };
+        let opcode: Opcode = opcode
+            .parse()
+            .map_err(|_| format!("unknown opcode \"{opcode}\""))?;
+        let (a, b, c) = (number(a)?, number(b)?, number(c)?);
+
+        // reads of the instruction pointer are the address of the instruction
+        let register = |r: usize| match ip_binding {
+            Some(ip) if ip == r => address.to_string(),
+            _ => format!("registers[{r}]"),
+        };
+        let value = |operand: Operand, v: usize| match operand {
+            Operand::Register => register(v),
+            Operand::Immediate | Operand::Ignored => v.to_string(),
+        };
+        let [kind1, kind2] = opcode.operands();
+        let (x, y) = (value(kind1, a), value(kind2, b));
+
+        let expression = match opcode.operator() {
+            Some("+") => format!("usize::wrapping_add({x}, {y})"),
+            Some("*") => format!("usize::wrapping_mul({x}, {y})"),
+            Some(operator) if opcode.is_comparison() => format!("({x} {operator} {y}) as usize"),
+            Some(operator) => format!("{x} {operator} {y}"),
+            None => x,
+        };
+
+        let next = match ip_binding {
+            Some(ip) if ip == c => format!("usize::wrapping_add(registers[{c}], 1)"),
+            _ => (address + 1).to_string(),
+        };
+
+        writeln!(
+            body,
+            "            // {line}\n            {address} => {{\n                registers[{c}] = {expression};\n                {next}\n            }}"
+        )
+        .unwrap();
+    }
+
+    let write_back = match ip_binding {
+        Some(ip) => format!("    registers[{ip}] = ip;\n"),
+        None => String::new(),
+    };
+
+    Ok(format!(
+        "\
+#[allow(clippy::all)]
+pub fn {name}<const R: usize>(registers: &mut [usize; R]) -> usize {{
+    let mut ip = 0;
+    let mut executed = 0;
+    loop {{
+        ip = match ip {{
+{body}            _ => break,
+        }};
+        executed += 1;
+    }}
+{write_back}    executed
+}}
+"
+    ))
+}
+
+fn number(s: &str) -> Result<usize, String> {
+    s.parse()
+        .map_err(|_| format!("expected a number, found \"{s}\""))
+}
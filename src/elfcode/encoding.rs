@@ -0,0 +1,279 @@
+//! Translates between the numeric programs of day 16, whose opcodes are only known by their
+//! number, and the mnemonic programs of days 19 and 21.
+//!
+//! ```text
+//! 9 2 1 2     <->     mulr 2 1 2
+//! ```
+//!
+//! Numeric programs are read and written with the same optional `#ip` declaration as
+//! mnemonic ones, so a program survives the trip in both directions.
+
+use std::collections::BTreeMap;
+
+use nom::{
+    bytes::complete::tag,
+    character::complete::{char, line_ending, multispace1, newline},
+    combinator::{map, map_opt, opt},
+    multi::separated_list1,
+    sequence::{delimited, preceded, separated_pair, terminated, tuple},
+    IResult,
+};
+
+use crate::parse::{blocks, lines, parse_all, unsigned, ParseError};
+
+use super::{
+    error_at,
+    infer::{infer, InferError, InstructionSet, Sample},
+    parse_program, Instruction, Opcode,
+};
+
+/// An instruction whose opcode is a number: `[number, input1, input2, output]`.
+pub type Numeric = [usize; 4];
+
+/// Which number stands for which opcode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Encoding {
+    opcodes: BTreeMap<usize, Opcode>,
+}
+
+impl Encoding {
+    pub fn new(opcodes: BTreeMap<usize, Opcode>) -> Self {
+        Self { opcodes }
+    }
+
+    /// Work out the encoding from samples, like the manual of day 16.
+    pub fn infer(samples: &[Sample<Opcode>]) -> Result<Self, InferError<Opcode>> {
+        infer(samples).map(Self::new)
+    }
+
+    pub fn opcode(&self, number: usize) -> Option<Opcode> {
+        self.opcodes.get(&number).copied()
+    }
+
+    pub fn number(&self, opcode: Opcode) -> Option<usize> {
+        self.opcodes
+            .iter()
+            .find(|(_, o)| **o == opcode)
+            .map(|(number, _)| *number)
+    }
+
+    /// The instruction, or `None` if its number is not part of the encoding.
+    pub fn decode_instruction(
+        &self,
+        [number, input1, input2, output]: Numeric,
+    ) -> Option<Instruction> {
+        Some(Instruction {
+            opcode: self.opcode(number)?,
+            input1,
+            input2,
+            output,
+        })
+    }
+
+    /// The numeric instruction, or `None` if its opcode is not part of the encoding.
+    pub fn encode_instruction(&self, instruction: Instruction) -> Option<Numeric> {
+        Some([
+            self.number(instruction.opcode)?,
+            instruction.input1,
+            instruction.input2,
+            instruction.output,
+        ])
+    }
+
+    /// Translate a numeric program into mnemonics, which [`parse_program`] reads.
+    pub fn decode(&self, source: &str) -> Result<String, ParseError> {
+        let (ip_binding, program) = parse_all(parse_numeric_program, source)?;
+        let first_line = if ip_binding.is_some() { 2 } else { 1 };
+
+        let mut output = header(ip_binding);
+        for (index, numeric) in program.into_iter().enumerate() {
+            let instruction = self.decode_instruction(numeric).ok_or_else(|| {
+                error_at(
+                    source,
+                    first_line + index,
+                    "an opcode number of the encoding",
+                )
+            })?;
+            output.push_str(&format!("{instruction}\n"));
+        }
+        Ok(output)
+    }
+
+    /// Translate a mnemonic program into numbers, which [`parse_numeric_program`] reads.
+    pub fn encode(&self, source: &str) -> Result<String, ParseError> {
+        let (ip_binding, program) = parse_all(parse_program, source)?;
+        let first_line = if ip_binding.is_some() { 2 } else { 1 };
+
+        let mut output = header(ip_binding);
+        for (index, instruction) in program.into_iter().enumerate() {
+            let [number, a, b, c] = self
+                .encode_instruction(instruction)
+                .ok_or_else(|| error_at(source, first_line + index, "an opcode of the encoding"))?;
+            output.push_str(&format!("{number} {a} {b} {c}\n"));
+        }
+        Ok(output)
+    }
+}
+
+fn header(ip_binding: Option<usize>) -> String {
+    ip_binding.map_or_else(String::new, |ip| format!("#ip {ip}\n"))
+}
+
+/// The manual of day 16: samples of unknown opcodes, then a numeric program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manual {
+    pub samples: Vec<Sample<Opcode>>,
+    pub program: Vec<Numeric>,
+}
+
+impl Manual {
+    /// Work out the encoding from the samples and decode the program with it.
+    pub fn decode(&self) -> Result<(Encoding, Vec<Instruction>), InferError<Opcode>> {
+        let encoding = Encoding::infer(&self.samples)?;
+        let mut program = vec![];
+        for numeric in &self.program {
+            match encoding.decode_instruction(*numeric) {
+                Some(instruction) => program.push(instruction),
+                // a number without samples could be any of the opcodes that are left
+                None => {
+                    return Err(InferError::Ambiguous {
+                        number: numeric[0],
+                        candidates: Opcode::opcodes()
+                            .into_iter()
+                            .filter(|opcode| encoding.number(*opcode).is_none())
+                            .collect(),
+                        samples: vec![],
+                    })
+                }
+            }
+        }
+        Ok((encoding, program))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+pub fn parse_numeric(i: &str) -> IResult<&str, Numeric> {
+    map(
+        tuple((
+            terminated(unsigned(), char(' ')),
+            terminated(unsigned(), char(' ')),
+            terminated(unsigned(), char(' ')),
+            unsigned(),
+        )),
+        |(number, input1, input2, output)| [number, input1, input2, output],
+    )(i)
+}
+
+/// Parse a numeric program, returning the register the instruction pointer is bound to, if any.
+pub fn parse_numeric_program(i: &str) -> IResult<&str, (Option<usize>, Vec<Numeric>)> {
+    tuple((
+        opt(terminated(preceded(tag("#ip "), unsigned()), newline)),
+        lines(parse_numeric),
+    ))(i)
+}
+
+fn parse_registers(i: &str) -> IResult<&str, Vec<usize>> {
+    delimited(char('['), separated_list1(tag(", "), unsigned()), char(']'))(i)
+}
+
+fn parse_sample(i: &str) -> IResult<&str, Sample<Opcode>> {
+    map_opt(
+        tuple((
+            delimited(tag("Before: "), parse_registers, line_ending),
+            terminated(parse_numeric, line_ending),
+            preceded(tag("After:  "), parse_registers),
+        )),
+        |(before, [number, a, b, c], after)| {
+            (before.len() == after.len()).then_some(Sample {
+                before,
+                number,
+                operands: [a, b, c],
+                after,
+            })
+        },
+    )(i)
+}
+
+/// Parse the manual of day 16.
+pub fn parse_manual(i: &str) -> IResult<&str, Manual> {
+    map(
+        separated_pair(blocks(parse_sample), multispace1, lines(parse_numeric)),
+        |(samples, program)| Manual { samples, program },
+    )(i)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{parse_manual, Encoding};
+    use crate::{
+        elfcode::{fixtures::COUNT, infer::InstructionSet, Machine, Opcode},
+        parse::parse_all,
+    };
+
+    /// Numbers the opcodes in reverse.
+    fn encoding() -> Encoding {
+        let opcodes = Opcode::opcodes();
+        Encoding::new(
+            opcodes
+                .iter()
+                .enumerate()
+                .map(|(i, opcode)| (opcodes.len() - 1 - i, *opcode))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn translates_both_ways() {
+        let encoding = encoding();
+        assert_eq!(encoding.opcode(15), Some(Opcode::addr));
+        assert_eq!(encoding.number(Opcode::eqrr), Some(0));
+
+        let numeric = encoding.encode(COUNT).unwrap();
+        assert_eq!(
+            numeric,
+            "#ip 4\n6 0 0 0\n14 0 1 0\n1 0 5 1\n15 1 4 4\n6 0 0 4\n13 4 4 4\n"
+        );
+        assert_eq!(encoding.decode(&numeric).unwrap(), COUNT);
+
+        // decoded programs run like the original
+        let mut machine = Machine::<6>::load(&encoding.decode(&numeric).unwrap()).unwrap();
+        machine.run();
+        assert_eq!(machine.registers[0], 5);
+    }
+
+    #[test]
+    fn points_at_unknown_opcodes() {
+        let encoding = Encoding::new(BTreeMap::from([(3, Opcode::seti)]));
+
+        let error = encoding.decode("3 1 0 0\n4 0 0 1\n").unwrap_err();
+        assert_eq!((error.line, error.text.as_str()), (2, "4 0 0 1"));
+        assert_eq!(error.expected, "an opcode number of the encoding");
+
+        let error = encoding
+            .encode("#ip 0\nseti 1 0 0\naddi 0 1 0\n")
+            .unwrap_err();
+        assert_eq!((error.line, error.text.as_str()), (3, "addi 0 1 0"));
+    }
+
+    #[test]
+    fn decodes_manuals() {
+        let manual = parse_all(
+            parse_manual,
+            "Before: [3, 2, 1, 1]\n9 2 1 2\nAfter:  [3, 2, 2, 1]\n\nBefore: [0, 0, 0, 0]\n9 2 1 2\nAfter:  [0, 0, 2, 0]\n\n\n\n9 5 0 0\n",
+        )
+        .unwrap();
+        assert_eq!(manual.samples.len(), 2);
+        assert_eq!(manual.samples[1].number, 9);
+        assert_eq!(manual.program, vec![[9, 5, 0, 0]]);
+
+        // only seti writes 2 from an input of 2
+        let (encoding, program) = manual.decode().unwrap();
+        assert_eq!(encoding.opcode(9), Some(Opcode::seti));
+        assert_eq!(program[0].to_string(), "seti 5 0 0");
+    }
+}
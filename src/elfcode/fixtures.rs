@@ -0,0 +1,15 @@
+//! Programs shared by the tests of the ElfCode modules.
+
+/// Counts r0 up to 5, then halts by jumping past the end of the program.
+pub const COUNT: &str =
+    "#ip 4\nseti 0 0 0\naddi 0 1 0\neqri 0 5 1\naddr 1 4 4\nseti 0 0 4\nmulr 4 4 4\n";
+
+/// Sums the divisors of 12 into r0, like day 19.
+pub const DIVISOR_SUM: &str = "#ip 3\nseti 12 0 5\nseti 1 5 1\nseti 1 2 2\nmulr 1 2 4\neqrr 4 5 4\naddr 4 3 3\naddi 3 1 3\naddr 1 0 0\naddi 2 1 2\ngtrr 2 5 4\naddr 3 4 3\nseti 2 7 3\naddi 1 1 1\ngtrr 1 5 4\naddr 4 3 3\nseti 1 9 3\n";
+
+/// Divides 1000 by 256 into r3, like day 21.
+pub const DIVIDE: &str = "#ip 1\nseti 1000 0 4\nseti 0 3 3\naddi 3 1 2\nmuli 2 256 2\ngtrr 2 4 2\naddr 2 1 1\naddi 1 1 1\nseti 9 3 1\naddi 3 1 3\nseti 1 0 1\n";
+
+/// Multiplies r0 by 3 a hundred times, which overflows every word.
+pub const OVERFLOW: &str =
+    "#ip 5\nseti 1 0 0\nmuli 0 3 0\naddi 1 1 1\ngtri 1 99 2\naddr 2 5 5\nseti 0 0 5\n";
@@ -0,0 +1,354 @@
+//! Works out which opcode each opcode number stands for, from samples of registers before
+//! and after an instruction, like the manual of day 16.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    error::Error,
+    fmt::{self, Debug},
+    hash::Hash,
+};
+
+use enum_iterator::all;
+use itertools::Itertools;
+
+use super::{word::Overflow, Instruction, Opcode};
+
+/// An instruction set whose opcodes can be tried on samples.
+pub trait InstructionSet: Copy + Eq + Hash + Debug {
+    type Registers: PartialEq + Debug;
+    type Operands: Copy + Debug;
+
+    /// Every opcode of the instruction set.
+    fn opcodes() -> Vec<Self>;
+
+    /// The registers after running the opcode with `operands`, or `None` if it can't run on
+    /// them, for example because they refer to a register that does not exist.
+    fn apply(self, before: &Self::Registers, operands: Self::Operands) -> Option<Self::Registers>;
+}
+
+impl InstructionSet for Opcode {
+    type Registers = Vec<usize>;
+    type Operands = [usize; 3];
+
+    fn opcodes() -> Vec<Self> {
+        all::<Opcode>().collect()
+    }
+
+    fn apply(
+        self,
+        before: &Vec<usize>,
+        [input1, input2, output]: [usize; 3],
+    ) -> Option<Vec<usize>> {
+        let instruction = Instruction {
+            opcode: self,
+            input1,
+            input2,
+            output,
+        };
+        if instruction
+            .reads()
+            .chain([output])
+            .any(|r| r >= before.len())
+        {
+            return None;
+        }
+
+        let mut after = before.clone();
+        after[output] = instruction.evaluate(before, Overflow::Wrapping)?;
+        Some(after)
+    }
+}
+
+/// The registers before and after an instruction whose opcode is only known by its number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sample<T: InstructionSet> {
+    pub before: T::Registers,
+    pub number: usize,
+    pub operands: T::Operands,
+    pub after: T::Registers,
+}
+
+impl<T: InstructionSet> Sample<T> {
+    /// The opcodes that turn `before` into `after`, in the order of [`InstructionSet::opcodes`].
+    pub fn candidates(&self) -> Vec<T> {
+        T::opcodes()
+            .into_iter()
+            .filter(|opcode| {
+                opcode.apply(&self.before, self.operands).as_ref() == Some(&self.after)
+            })
+            .collect()
+    }
+}
+
+/// An error which can be returned by [`infer`]. Samples are referred to by their index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InferError<T> {
+    /// No mapping fits all the samples of these opcode numbers.
+    Unsatisfiable {
+        numbers: Vec<usize>,
+        samples: Vec<usize>,
+    },
+    /// The samples allow more than one opcode for this number.
+    Ambiguous {
+        number: usize,
+        candidates: Vec<T>,
+        samples: Vec<usize>,
+    },
+}
+
+impl<T: Debug> Error for InferError<T> {}
+
+impl<T: Debug> fmt::Display for InferError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InferError::Unsatisfiable { numbers, samples } => write!(
+                f,
+                "no opcodes fit opcode numbers {}, see samples {}",
+                numbers.iter().join(", "),
+                samples.iter().join(", ")
+            ),
+            InferError::Ambiguous {
+                number,
+                candidates,
+                samples,
+            } => write!(
+                f,
+                "opcode number {number} could be any of {}, see samples {}",
+                candidates.iter().map(|c| format!("{c:?}")).join(", "),
+                samples.iter().join(", ")
+            ),
+        }
+    }
+}
+
+/// Find the opcode of every number that appears in the samples.
+///
+/// Each number narrows down to the opcodes that fit all of its samples. Numbers left with a
+/// single opcode rule it out for the others, and when that is not enough, the remaining
+/// choices are searched for the only mapping that gives every number a different opcode.
+pub fn infer<T: InstructionSet>(
+    samples: &[Sample<T>],
+) -> Result<BTreeMap<usize, T>, InferError<T>> {
+    let mut candidates: BTreeMap<usize, Vec<T>> = BTreeMap::new();
+    // the samples that narrowed down each number, to point at when it goes wrong
+    let mut evidence: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+
+    for (index, sample) in samples.iter().enumerate() {
+        let fits = sample.candidates();
+        let current = candidates.entry(sample.number).or_insert_with(T::opcodes);
+        let before = current.len();
+        current.retain(|opcode| fits.contains(opcode));
+
+        if current.len() < before {
+            evidence.entry(sample.number).or_default().push(index);
+        }
+        if current.is_empty() {
+            return Err(InferError::Unsatisfiable {
+                numbers: vec![sample.number],
+                samples: evidence[&sample.number].clone(),
+            });
+        }
+    }
+
+    let original = candidates.clone();
+    propagate(&mut candidates);
+
+    let mut solutions = vec![];
+    search(&candidates, &mut BTreeMap::new(), &mut solutions);
+
+    match &solutions[..] {
+        [solution] => Ok(solution.clone()),
+        [] => {
+            // the numbers that could not be settled, and the settled ones they compete with
+            let unsettled: Vec<usize> = candidates
+                .iter()
+                .filter(|(_, opcodes)| opcodes.len() != 1)
+                .map(|(number, _)| *number)
+                .collect();
+            let numbers: Vec<usize> = candidates
+                .iter()
+                .filter(|(number, opcodes)| {
+                    unsettled.contains(number)
+                        || unsettled.iter().any(|u| original[u].contains(&opcodes[0]))
+                })
+                .map(|(number, _)| *number)
+                .collect();
+            Err(InferError::Unsatisfiable {
+                samples: samples_of(samples, &numbers),
+                numbers,
+            })
+        }
+        [first, second, ..] => {
+            let number = *first
+                .keys()
+                .find(|number| first[number] != second[number])
+                .expect("different solutions differ somewhere");
+            Err(InferError::Ambiguous {
+                number,
+                candidates: candidates[&number].clone(),
+                samples: samples_of(samples, &[number]),
+            })
+        }
+    }
+}
+
+fn samples_of<T: InstructionSet>(samples: &[Sample<T>], numbers: &[usize]) -> Vec<usize> {
+    samples
+        .iter()
+        .positions(|sample| numbers.contains(&sample.number))
+        .collect()
+}
+
+/// Remove the opcodes of numbers that have only one left from every other number,
+/// until nothing changes.
+fn propagate<T: InstructionSet>(candidates: &mut BTreeMap<usize, Vec<T>>) {
+    let mut settled = BTreeSet::new();
+
+    while let Some((number, opcode)) = candidates
+        .iter()
+        .find(|(number, opcodes)| opcodes.len() == 1 && !settled.contains(*number))
+        .map(|(number, opcodes)| (*number, opcodes[0]))
+    {
+        settled.insert(number);
+        for (_, opcodes) in candidates.iter_mut().filter(|(other, _)| **other != number) {
+            opcodes.retain(|o| *o != opcode);
+        }
+    }
+}
+
+/// Try the remaining choices, the most constrained number first. Stops after two solutions,
+/// which is enough to tell that the samples are ambiguous.
+fn search<T: InstructionSet>(
+    candidates: &BTreeMap<usize, Vec<T>>,
+    chosen: &mut BTreeMap<usize, T>,
+    solutions: &mut Vec<BTreeMap<usize, T>>,
+) {
+    let available = |opcodes: &Vec<T>| -> Vec<T> {
+        opcodes
+            .iter()
+            .filter(|opcode| !chosen.values().any(|c| c == *opcode))
+            .copied()
+            .collect()
+    };
+
+    let Some((number, options)) = candidates
+        .iter()
+        .filter(|(number, _)| !chosen.contains_key(number))
+        .map(|(number, opcodes)| (*number, available(opcodes)))
+        .min_by_key(|(_, options)| options.len())
+    else {
+        solutions.push(chosen.clone());
+        return;
+    };
+
+    for opcode in options {
+        chosen.insert(number, opcode);
+        search(candidates, chosen, solutions);
+        chosen.remove(&number);
+
+        if solutions.len() > 1 {
+            return;
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{infer, InferError, InstructionSet, Sample};
+    use crate::elfcode::Opcode;
+
+    fn sample(
+        before: [usize; 4],
+        number: usize,
+        operands: [usize; 3],
+        after: [usize; 4],
+    ) -> Sample<Opcode> {
+        Sample {
+            before: before.to_vec(),
+            number,
+            operands,
+            after: after.to_vec(),
+        }
+    }
+
+    /// A sample for every opcode, numbered in reverse, with registers that tell them apart.
+    fn samples() -> Vec<Sample<Opcode>> {
+        let opcodes = Opcode::opcodes();
+        let mut samples = vec![];
+        for (i, opcode) in opcodes.iter().enumerate() {
+            for (before, operands) in [
+                ([3, 5, 9, 2], [1, 2, 3]),
+                ([7, 1, 7, 3], [2, 0, 1]),
+                ([0, 6, 4, 1], [1, 3, 0]),
+                ([4, 1, 0, 2], [3, 1, 0]),
+            ] {
+                let after = opcode.apply(&before.to_vec(), operands).unwrap();
+                samples.push(Sample {
+                    before: before.to_vec(),
+                    number: opcodes.len() - 1 - i,
+                    operands,
+                    after,
+                });
+            }
+        }
+        samples
+    }
+
+    #[test]
+    fn finds_candidates() {
+        let sample = sample([3, 2, 1, 1], 9, [2, 1, 2], [3, 2, 2, 1]);
+        assert_eq!(
+            sample.candidates(),
+            vec![Opcode::addi, Opcode::mulr, Opcode::seti]
+        );
+
+        // register 4 does not exist
+        assert_eq!(Opcode::addr.apply(&vec![0; 4], [4, 0, 0]), None);
+    }
+
+    #[test]
+    fn infers_mappings() {
+        let mapping = infer(&samples()).unwrap();
+        assert_eq!(mapping.len(), 16);
+        assert_eq!(mapping[&15], Opcode::addr);
+        assert_eq!(mapping[&0], Opcode::eqrr);
+    }
+
+    #[test]
+    fn reports_conflicts() {
+        // seti can't write 9 when its input is 2
+        let samples = vec![
+            sample([3, 2, 1, 1], 9, [2, 1, 2], [3, 2, 2, 1]),
+            sample([0, 0, 0, 0], 9, [2, 1, 2], [0, 0, 9, 0]),
+        ];
+        assert_eq!(
+            infer(&samples),
+            Err(InferError::Unsatisfiable {
+                numbers: vec![9],
+                samples: vec![0, 1]
+            })
+        );
+
+        // both numbers can only be seti
+        let samples = vec![
+            sample([0, 0, 0, 0], 1, [5, 0, 0], [5, 0, 0, 0]),
+            sample([0, 0, 0, 0], 2, [5, 0, 1], [0, 5, 0, 0]),
+        ];
+        assert_eq!(
+            infer(&samples),
+            Err(InferError::Unsatisfiable {
+                numbers: vec![1, 2],
+                samples: vec![0, 1]
+            })
+        );
+
+        let samples = vec![sample([3, 2, 1, 1], 9, [2, 1, 2], [3, 2, 2, 1])];
+        let error = infer(&samples).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "opcode number 9 could be any of addi, mulr, seti, see samples 0"
+        );
+    }
+}
@@ -0,0 +1,492 @@
+//! The ElfCode virtual machine from days 16, 19 and 21.
+
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use itertools::Itertools;
+use nom::{
+    bytes::complete::tag,
+    character::complete::{alpha1, char, newline},
+    combinator::{map_res, opt},
+    sequence::{preceded, terminated, tuple},
+    IResult,
+};
+
+use crate::parse::{lines, parse_all, unsigned, ParseError};
+
+pub use self::opcode::{Opcode, Operand};
+
+use self::{
+    compile::{compile, Compiled},
+    optimizer::{optimize, Native},
+    word::{Overflow, OverflowError, Word},
+};
+
+pub mod asm;
+pub mod compile;
+pub mod cycle;
+pub mod debugger;
+pub mod disasm;
+pub mod emit;
+pub mod encoding;
+#[cfg(feature = "test_lib")]
+mod fixtures;
+pub mod infer;
+pub mod native;
+mod opcode;
+pub mod optimizer;
+pub mod profiler;
+pub mod word;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Instruction {
+    pub opcode: Opcode,
+    pub input1: usize,
+    pub input2: usize,
+    pub output: usize,
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:?} {} {} {}",
+            self.opcode, self.input1, self.input2, self.output
+        )
+    }
+}
+
+impl Instruction {
+    pub const fn new(opcode: Opcode, input1: usize, input2: usize, output: usize) -> Self {
+        Instruction {
+            opcode,
+            input1,
+            input2,
+            output,
+        }
+    }
+
+    /// The registers the instruction reads.
+    pub fn reads(&self) -> impl Iterator<Item = usize> {
+        self.opcode
+            .operands()
+            .into_iter()
+            .zip([self.input1, self.input2])
+            .filter(|(operand, _)| *operand == Operand::Register)
+            .map(|(_, register)| register)
+    }
+
+    /// Whether the instruction only reads and writes registers below `registers`.
+    fn fits(&self, registers: usize) -> bool {
+        self.output < registers && self.reads().all(|register| register < registers)
+    }
+
+    /// The value the instruction writes to its output register, or `None` if it overflows
+    /// while `overflow` is [`Overflow::Checked`].
+    ///
+    /// # Panics
+    /// If the instruction reads a register that does not exist.
+    fn evaluate<W: Word>(&self, registers: &[W], overflow: Overflow) -> Option<W> {
+        let Instruction {
+            opcode,
+            input1: a,
+            input2: b,
+            ..
+        } = *self;
+        let (a_value, b_value) = (W::from_usize(a), W::from_usize(b));
+
+        Some(match opcode {
+            Opcode::addr => registers[a].add(registers[b], overflow)?,
+            Opcode::addi => registers[a].add(b_value, overflow)?,
+            Opcode::mulr => registers[a].mul(registers[b], overflow)?,
+            Opcode::muli => registers[a].mul(b_value, overflow)?,
+            Opcode::banr => registers[a].and(registers[b]),
+            Opcode::bani => registers[a].and(b_value),
+            Opcode::borr => registers[a].or(registers[b]),
+            Opcode::bori => registers[a].or(b_value),
+            Opcode::setr => registers[a],
+            Opcode::seti => a_value,
+            Opcode::gtir => W::from_bool(a_value > registers[b]),
+            Opcode::gtri => W::from_bool(registers[a] > b_value),
+            Opcode::gtrr => W::from_bool(registers[a] > registers[b]),
+            Opcode::eqir => W::from_bool(a_value == registers[b]),
+            Opcode::eqri => W::from_bool(registers[a] == b_value),
+            Opcode::eqrr => W::from_bool(registers[a] == registers[b]),
+        })
+    }
+}
+
+/// Execute a single instruction on a copy of `registers`, without touching the instruction pointer.
+/// Arithmetic wraps around on overflow.
+///
+/// # Panics
+/// If the instruction refers to a register that does not exist.
+pub fn execute<const R: usize, W: Word>(registers: [W; R], instruction: Instruction) -> [W; R] {
+    let mut registers = registers;
+    registers[instruction.output] = instruction
+        .evaluate(&registers, Overflow::Wrapping)
+        .expect("wrapping arithmetic never overflows");
+    registers
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// An ElfCode machine with `R` registers that hold a `W` each.
+///
+/// If the instruction pointer is bound to a register, that register mirrors the pointer:
+/// instructions can read it, and writing to it makes the program jump. Jumping to a negative
+/// address halts the machine, like any other address outside the program.
+#[derive(Debug, Clone)]
+pub struct Machine<const R: usize, W: Word = usize> {
+    pub registers: [W; R],
+    pub program: Vec<Instruction>,
+    ip: usize,
+    ip_binding: Option<usize>,
+    executed: usize,
+    natives: BTreeMap<usize, Native>,
+    overflow: Overflow,
+}
+
+impl<const R: usize, W: Word> Machine<R, W> {
+    /// Create a machine with all registers set to zero, which wraps around on overflow.
+    ///
+    /// # Panics
+    /// If the instruction pointer is bound to, or an instruction uses, a register that does not
+    /// exist. [`Machine::load`] reports those as errors instead.
+    pub fn new(ip_binding: Option<usize>, program: Vec<Instruction>) -> Self {
+        assert!(
+            ip_binding.is_none_or(|register| register < R),
+            "the machine only has {R} registers"
+        );
+        if let Some(index) = program.iter().position(|instruction| !instruction.fits(R)) {
+            panic!("instruction {index} uses a register that does not exist");
+        }
+
+        Machine {
+            registers: [W::default(); R],
+            program,
+            ip: 0,
+            ip_binding,
+            executed: 0,
+            natives: BTreeMap::new(),
+            overflow: Overflow::default(),
+        }
+    }
+
+    /// Use another overflow policy.
+    pub fn with_overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    pub fn overflow(&self) -> Overflow {
+        self.overflow
+    }
+
+    /// Parse a program, with an optional `#ip` declaration on its first line.
+    ///
+    /// Registers that do not exist are reported as errors too.
+    pub fn load(input: &str) -> Result<Self, ParseError> {
+        let (ip_binding, program) = parse_all(parse_program, input)?;
+        let expected = format!("a register below {R}");
+
+        if ip_binding.is_some_and(|register| register >= R) {
+            return Err(error_at(input, 1, &expected));
+        }
+        let first_line = if ip_binding.is_some() { 2 } else { 1 };
+        if let Some(index) = program.iter().position(|instruction| !instruction.fits(R)) {
+            return Err(error_at(input, first_line + index, &expected));
+        }
+
+        Ok(Machine::new(ip_binding, program))
+    }
+
+    /// The index of the next instruction to execute.
+    pub fn ip(&self) -> usize {
+        self.ip
+    }
+
+    /// Jump to another instruction, updating the bound register too.
+    pub fn set_ip(&mut self, ip: usize) {
+        self.ip = ip;
+        if let Some(register) = self.ip_binding {
+            self.registers[register] = W::from_usize(ip);
+        }
+    }
+
+    pub fn ip_binding(&self) -> Option<usize> {
+        self.ip_binding
+    }
+
+    /// The number of instructions executed so far.
+    pub fn executed(&self) -> usize {
+        self.executed
+    }
+
+    /// Replace the loops the optimizer recognizes with native operations, so that each of them
+    /// runs as a single step. Returns the number of loops replaced.
+    pub fn optimize(&mut self) -> usize {
+        self.natives = optimize(self.ip_binding, &self.program);
+        self.natives.len()
+    }
+
+    /// The native operations that replace loops, keyed by the address they start at.
+    pub fn natives(&self) -> &BTreeMap<usize, Native> {
+        &self.natives
+    }
+
+    /// The next instruction to execute, or `None` if the machine has halted.
+    pub fn current(&self) -> Option<Instruction> {
+        self.program.get(self.ip).copied()
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.current().is_none()
+    }
+
+    /// Execute one instruction, or a whole loop if it was optimized.
+    /// Returns false, and does nothing, if the machine has halted.
+    ///
+    /// # Panics
+    /// If the instruction overflows while the machine checks for overflows,
+    /// see [`Machine::try_step`].
+    pub fn step(&mut self) -> bool {
+        self.try_step().unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Like [`Machine::step`], but an overflow is returned as an error,
+    /// and leaves the machine as it was before the instruction.
+    pub fn try_step(&mut self) -> Result<bool, OverflowError> {
+        let Some(instruction) = self.current() else {
+            return Ok(false);
+        };
+
+        // loops the native can't stand in for run instruction by instruction
+        let native = self.natives.get(&self.ip);
+        if let Some(exit) = native.and_then(|n| n.apply(&mut self.registers, self.overflow)) {
+            self.set_ip(exit);
+            self.executed += 1;
+            return Ok(true);
+        }
+
+        let error = OverflowError {
+            address: self.ip,
+            instruction,
+        };
+        let mut registers = self.registers;
+        registers[instruction.output] = instruction
+            .evaluate(&self.registers, self.overflow)
+            .ok_or(error)?;
+
+        match self.ip_binding {
+            Some(register) => {
+                let ip = registers[register]
+                    .add(W::ONE, self.overflow)
+                    .ok_or(error)?;
+                registers[register] = ip;
+                self.registers = registers;
+                self.ip = ip.to_usize().unwrap_or(usize::MAX);
+            }
+            None => {
+                self.registers = registers;
+                self.ip += 1;
+            }
+        }
+        self.executed += 1;
+        Ok(true)
+    }
+
+    /// Run until the machine halts. Programs that never halt make this loop forever.
+    ///
+    /// # Panics
+    /// If an instruction overflows while the machine checks for overflows,
+    /// see [`Machine::try_run`].
+    pub fn run(&mut self) {
+        while self.step() {}
+    }
+
+    /// Like [`Machine::run`], but stops at the first instruction that overflows.
+    pub fn try_run(&mut self) -> Result<(), OverflowError> {
+        while self.try_step()? {}
+        Ok(())
+    }
+
+    /// Run until `predicate` holds before the next instruction.
+    /// Returns false if the machine halted first.
+    pub fn run_until(&mut self, mut predicate: impl FnMut(&Self) -> bool) -> bool {
+        loop {
+            if predicate(self) {
+                return true;
+            }
+            if !self.step() {
+                return false;
+            }
+        }
+    }
+
+    /// Run at most `limit` instructions.
+    /// Returns the number of instructions executed if the machine halted within the limit.
+    pub fn run_with_limit(&mut self, limit: usize) -> Option<usize> {
+        let start = self.executed;
+        while self.executed - start < limit {
+            if !self.step() {
+                return Some(self.executed - start);
+            }
+        }
+        self.is_halted().then_some(limit)
+    }
+
+    /// Compile the program, including the loops replaced by `optimize`, to run it faster
+    /// than `step` can. The compiled program follows the overflow policy of this machine,
+    /// and runs on it or any of its clones.
+    pub fn compile(&self) -> Compiled<R, W> {
+        compile(self.ip_binding, &self.program, &self.natives, self.overflow)
+    }
+}
+
+impl<const R: usize, W: Word> fmt::Display for Machine<R, W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "ip={} [{}]",
+            self.ip,
+            self.registers.iter().map(|i| i.to_string()).join(", ")
+        )
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+pub fn parse_instruction(i: &str) -> IResult<&str, Instruction> {
+    let (i, (opcode, input1, _, input2, _, output)) = tuple((
+        terminated(map_res(alpha1, Opcode::from_str), char(' ')),
+        unsigned(),
+        char(' '),
+        unsigned(),
+        char(' '),
+        unsigned(),
+    ))(i)?;
+
+    Ok((
+        i,
+        Instruction {
+            opcode,
+            input1,
+            input2,
+            output,
+        },
+    ))
+}
+
+/// An error pointing at the start of `line`, which starts at 1.
+fn error_at(source: &str, line: usize, expected: &str) -> ParseError {
+    ParseError {
+        line,
+        column: 1,
+        text: source.lines().nth(line - 1).unwrap_or_default().to_string(),
+        expected: expected.to_string(),
+    }
+}
+
+/// Parse a program, returning the register the instruction pointer is bound to, if any.
+pub fn parse_program(i: &str) -> IResult<&str, (Option<usize>, Vec<Instruction>)> {
+    tuple((
+        opt(terminated(preceded(tag("#ip "), unsigned()), newline)),
+        lines(parse_instruction),
+    ))(i)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{execute, word::Overflow, Instruction, Machine, Opcode};
+
+    const PROGRAM: &str = "#ip 0\nseti 5 0 1\nseti 6 0 2\naddi 0 1 0\naddr 1 2 3\nsetr 1 0 0\nseti 8 0 4\nseti 9 0 5\n";
+
+    #[test]
+    fn executes_instructions() {
+        let instruction = Instruction {
+            opcode: Opcode::mulr,
+            input1: 2,
+            input2: 1,
+            output: 2,
+        };
+        assert_eq!(execute([3, 2, 1, 1], instruction), [3, 2, 2, 1]);
+    }
+
+    #[test]
+    fn runs_bound_programs() {
+        let mut machine = Machine::<6>::load(PROGRAM).unwrap();
+        assert_eq!(machine.ip_binding(), Some(0));
+        assert_eq!(machine.program.len(), 7);
+
+        assert!(machine.run_until(|m| m.ip() == 6));
+        assert_eq!(machine.registers, [6, 5, 6, 0, 0, 0]);
+
+        machine.run();
+        assert!(machine.is_halted());
+        assert_eq!(machine.registers, [7, 5, 6, 0, 0, 9]);
+        assert_eq!(machine.executed(), 5);
+        assert!(!machine.step());
+    }
+
+    #[test]
+    fn runs_unbound_programs_with_a_limit() {
+        let mut machine = Machine::<4>::load("seti 3 0 0\naddi 0 1 1\n").unwrap();
+        assert_eq!(machine.ip_binding(), None);
+        assert_eq!(machine.run_with_limit(1), None);
+        assert_eq!(machine.run_with_limit(5), Some(1));
+        assert_eq!(machine.registers, [3, 4, 0, 0]);
+
+        // jumps back to the start forever
+        let mut machine = Machine::<2>::load("#ip 1\nseti 0 0 0\nseti 0 0 1\n").unwrap();
+        assert_eq!(machine.run_with_limit(100), None);
+        assert_eq!(machine.executed(), 100);
+    }
+
+    #[test]
+    fn rejects_registers_that_do_not_exist() {
+        let error = Machine::<4>::load("#ip 5\nseti 0 0 0\n").unwrap_err();
+        assert_eq!((error.line, error.text.as_str()), (1, "#ip 5"));
+        assert_eq!(error.expected, "a register below 4");
+
+        let error = Machine::<4>::load("#ip 3\nseti 0 0 0\naddr 0 4 1\n").unwrap_err();
+        assert_eq!((error.line, error.text.as_str()), (3, "addr 0 4 1"));
+
+        // immediate operands can be as large as they like
+        assert!(Machine::<4>::load("seti 7 9 0\naddi 0 100 3\n").is_ok());
+        assert!(Machine::<4>::load("seti 0 0 4\n").is_err());
+    }
+
+    #[test]
+    fn follows_the_word_size_and_overflow_policy() {
+        // doubles r0 forever
+        const DOUBLING: &str = "#ip 2\nseti 1 0 0\nmuli 0 2 0\nseti 0 0 2\n";
+
+        let mut machine = Machine::<3, u32>::load(DOUBLING).unwrap();
+        machine.run_with_limit(1 + 2 * 32);
+        assert_eq!(machine.registers[0], 0);
+
+        let mut machine = Machine::<3, u32>::load(DOUBLING)
+            .unwrap()
+            .with_overflow(Overflow::Saturating);
+        machine.run_with_limit(1 + 2 * 33);
+        assert_eq!(machine.registers[0], u32::MAX);
+
+        let mut machine = Machine::<3, u64>::load(DOUBLING)
+            .unwrap()
+            .with_overflow(Overflow::Checked);
+        let error = machine.try_run().unwrap_err();
+        assert_eq!(error.to_string(), "instruction 01 overflowed: muli 0 2 0");
+        // the instruction that overflowed did not run
+        assert_eq!(machine.registers[0], 1 << 63);
+        assert_eq!(machine.ip(), 1);
+
+        // jumping to a negative address halts
+        let mut machine = Machine::<2, i64>::load("#ip 1\nsetr 0 0 1\n").unwrap();
+        machine.registers[0] = -5;
+        machine.run();
+        assert!(machine.is_halted());
+        assert_eq!(machine.registers, [-5, -4]);
+    }
+}
@@ -0,0 +1,59 @@
+//! The programs listed in `ELFCODE_NATIVE` at build time, compiled to Rust by `emit`.
+//!
+//! With the `test_lib` feature, the test programs of `fixtures` are compiled in as well, as
+//! `fixture_count`, `fixture_divisor_sum` and so on.
+
+include!(concat!(env!("OUT_DIR"), "/elfcode.rs"));
+
+/* -------------------------------------------------------------------------- */
+
+// the tests of `emit` live here, since the build script includes that module without a harness
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{fixture_count, fixture_divide, fixture_divisor_sum, fixture_overflow};
+    use crate::elfcode::{
+        emit::emit,
+        fixtures::{COUNT, DIVIDE, DIVISOR_SUM, OVERFLOW},
+        Machine,
+    };
+
+    #[test]
+    fn emits_functions() {
+        let source = emit("count", COUNT).unwrap();
+
+        assert!(
+            source.contains("pub fn count<const R: usize>(registers: &mut [usize; R]) -> usize {")
+        );
+        assert!(source.contains("            1 => {\n                registers[0] = usize::wrapping_add(registers[0], 1);\n                2\n"));
+        assert!(source.contains("registers[1] = (registers[0] == 5) as usize;"));
+        // the ip register reads as the address, and writing it jumps
+        assert!(source.contains(
+            "registers[4] = usize::wrapping_add(registers[1], 3);\n                usize::wrapping_add(registers[4], 1)\n"
+        ));
+        assert!(source.contains("    registers[4] = ip;\n    executed\n"));
+
+        assert!(emit("broken", "seti 0 0\n").is_err());
+        assert!(emit("broken", "jump 0 0 0\n").is_err());
+    }
+
+    #[test]
+    fn runs_like_the_interpreter() {
+        let natives: [(&str, fn(&mut [usize; 6]) -> usize); 4] = [
+            (COUNT, fixture_count),
+            (DIVISOR_SUM, fixture_divisor_sum),
+            (DIVIDE, fixture_divide),
+            // wraps around like the machine
+            (OVERFLOW, fixture_overflow),
+        ];
+
+        for (program, native) in natives {
+            let mut machine = Machine::<6>::load(program).unwrap();
+            let mut registers = machine.registers;
+            let executed = native(&mut registers);
+
+            machine.run();
+            assert_eq!(registers, machine.registers);
+            assert_eq!(executed, machine.executed());
+        }
+    }
+}
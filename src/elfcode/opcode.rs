@@ -0,0 +1,94 @@
+//! The opcodes of ElfCode and how they use their inputs.
+
+use std::str::FromStr;
+
+use enum_iterator::Sequence;
+
+#[allow(non_camel_case_types)]
+#[derive(Hash, PartialEq, Eq, Sequence, Clone, Copy, Debug)]
+pub enum Opcode {
+    addr,
+    addi,
+    mulr,
+    muli,
+    banr,
+    bani,
+    borr,
+    bori,
+    setr,
+    seti,
+    gtir,
+    gtri,
+    gtrr,
+    eqir,
+    eqri,
+    eqrr,
+}
+
+impl FromStr for Opcode {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<Opcode, Self::Err> {
+        match input {
+            "addr" => Ok(Opcode::addr),
+            "addi" => Ok(Opcode::addi),
+            "mulr" => Ok(Opcode::mulr),
+            "muli" => Ok(Opcode::muli),
+            "banr" => Ok(Opcode::banr),
+            "bani" => Ok(Opcode::bani),
+            "borr" => Ok(Opcode::borr),
+            "bori" => Ok(Opcode::bori),
+            "setr" => Ok(Opcode::setr),
+            "seti" => Ok(Opcode::seti),
+            "gtir" => Ok(Opcode::gtir),
+            "gtri" => Ok(Opcode::gtri),
+            "gtrr" => Ok(Opcode::gtrr),
+            "eqir" => Ok(Opcode::eqir),
+            "eqri" => Ok(Opcode::eqri),
+            "eqrr" => Ok(Opcode::eqrr),
+            _ => Err(()),
+        }
+    }
+}
+
+/// How an instruction uses one of its inputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    Register,
+    Immediate,
+    Ignored,
+}
+
+impl Opcode {
+    /// How the two inputs of the opcode are used.
+    pub fn operands(self) -> [Operand; 2] {
+        use Operand::*;
+        match self {
+            Opcode::addr | Opcode::mulr | Opcode::banr | Opcode::borr => [Register, Register],
+            Opcode::gtrr | Opcode::eqrr => [Register, Register],
+            Opcode::addi | Opcode::muli | Opcode::bani | Opcode::bori => [Register, Immediate],
+            Opcode::gtri | Opcode::eqri => [Register, Immediate],
+            Opcode::gtir | Opcode::eqir => [Immediate, Register],
+            Opcode::setr => [Register, Ignored],
+            Opcode::seti => [Immediate, Ignored],
+        }
+    }
+
+    /// The operator of the opcode in pseudocode, or `None` for the `set` opcodes.
+    pub fn operator(self) -> Option<&'static str> {
+        match self {
+            Opcode::addr | Opcode::addi => Some("+"),
+            Opcode::mulr | Opcode::muli => Some("*"),
+            Opcode::banr | Opcode::bani => Some("&"),
+            Opcode::borr | Opcode::bori => Some("|"),
+            Opcode::gtir | Opcode::gtri | Opcode::gtrr => Some(">"),
+            Opcode::eqir | Opcode::eqri | Opcode::eqrr => Some("=="),
+            Opcode::setr | Opcode::seti => None,
+        }
+    }
+
+    /// Returns true for the opcodes that write 0 or 1.
+    pub fn is_comparison(self) -> bool {
+        matches!(self.operator(), Some(">" | "=="))
+    }
+}
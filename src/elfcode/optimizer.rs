@@ -0,0 +1,385 @@
+//! Recognizes slow loops in ElfCode programs and replaces them with native operations.
+//!
+//! A replaced loop still leaves every register exactly as running it would have,
+//! it just gets there in a single step.
+
+use std::collections::BTreeMap;
+
+use super::{
+    disasm::{flow, Flow},
+    word::{Overflow, Word},
+    Instruction, Opcode,
+};
+
+/// A native operation standing in for the loop that starts at its address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Native {
+    /// Two nested loops that try every `a * b == n` and add the matching `a` to `sum`,
+    /// which adds the sum of the divisors of `n`.
+    DivisorSum {
+        n: usize,
+        sum: usize,
+        a: usize,
+        b: usize,
+        flag: usize,
+        exit: usize,
+    },
+    /// A loop that counts `quotient` up until `(quotient + 1) * divisor > dividend`.
+    Divide {
+        dividend: usize,
+        divisor: usize,
+        quotient: usize,
+        flag: usize,
+        exit: usize,
+    },
+}
+
+impl Native {
+    /// Update the registers like the loop would, returning the address the loop exits to.
+    /// Negative values count as zero, since the loops never go around for them.
+    ///
+    /// Returns `None`, and leaves the registers alone, if the loop would overflow a register
+    /// on the way, or overflow its result while `overflow` is [`Overflow::Checked`]. Running
+    /// the loop instruction by instruction then does exactly what the policy says.
+    pub fn apply<W: Word>(&self, registers: &mut [W], overflow: Overflow) -> Option<usize> {
+        match *self {
+            Native::DivisorSum {
+                n,
+                sum,
+                a,
+                b,
+                flag,
+                exit,
+            } => {
+                let n = registers[n].to_usize().unwrap_or(0);
+                // the loops multiply every pair of numbers up to n
+                let last: W = word(n.max(1) + 1)?;
+                last.mul(last, Overflow::Checked)?;
+
+                registers[sum] = registers[sum].add(word(divisor_sum(n))?, overflow)?;
+                // both loops run at least once, even when there is nothing to find
+                registers[a] = last;
+                registers[b] = last;
+                registers[flag] = W::ONE;
+                Some(exit)
+            }
+            Native::Divide {
+                dividend,
+                divisor,
+                quotient,
+                flag,
+                exit,
+            } => {
+                let dividend = registers[dividend].to_usize().unwrap_or(0);
+                // the loop stops once (quotient + 1) * divisor gets past the dividend
+                let q = dividend / divisor;
+                word::<W>(q + 1)?.mul(word(divisor)?, Overflow::Checked)?;
+
+                registers[quotient] = word(q)?;
+                registers[flag] = W::ONE;
+                Some(exit)
+            }
+        }
+    }
+}
+
+/// `value` as a word, or `None` if it doesn't fit.
+fn word<W: Word>(value: usize) -> Option<W> {
+    let word = W::from_usize(value);
+    (word.to_usize() == Some(value)).then_some(word)
+}
+
+fn divisor_sum(n: usize) -> usize {
+    (1..)
+        .take_while(|d| d * d <= n)
+        .filter(|d| n.is_multiple_of(*d))
+        .map(|d| if d * d == n { d } else { d + n / d })
+        .sum()
+}
+
+/// Find the loops that can be replaced, keyed by the address they start at.
+pub fn optimize(ip_binding: Option<usize>, program: &[Instruction]) -> BTreeMap<usize, Native> {
+    let Some(ip) = ip_binding else {
+        // without a bound instruction pointer, programs can't loop
+        return BTreeMap::new();
+    };
+
+    (0..program.len())
+        .filter_map(|address| {
+            let matcher = Matcher { ip, address };
+            matcher
+                .divisor_sum(&program[address..])
+                .or_else(|| matcher.divide(&program[address..]))
+                .map(|native| (address, native))
+        })
+        .collect()
+}
+
+struct Matcher {
+    ip: usize,
+    address: usize,
+}
+
+impl Matcher {
+    /// `seti value _ r`, returning `r`.
+    fn set(&self, instruction: &Instruction, value: usize) -> Option<usize> {
+        (instruction.opcode == Opcode::seti && instruction.input1 == value)
+            .then_some(instruction.output)
+    }
+
+    /// An instruction with the given opcode that reads `x`, returning its other input.
+    fn with(&self, instruction: &Instruction, opcode: Opcode, x: usize) -> Option<usize> {
+        if instruction.opcode != opcode {
+            None
+        } else if instruction.input1 == x {
+            Some(instruction.input2)
+        } else if instruction.input2 == x {
+            Some(instruction.input1)
+        } else {
+            None
+        }
+    }
+
+    fn is_increment(&self, instruction: &Instruction, register: usize) -> bool {
+        instruction.opcode == Opcode::addi
+            && (instruction.input1, instruction.input2, instruction.output)
+                == (register, 1, register)
+    }
+
+    /// The control flow of the instruction at `offset` from the start of the loop.
+    fn flow(&self, program: &[Instruction], offset: usize) -> Flow {
+        flow(Some(self.ip), self.address + offset, program[offset])
+    }
+
+    /// `if flag: skip`, then a jump over the next instruction, starting at `offset`.
+    fn is_skip_over(&self, program: &[Instruction], offset: usize, flag: usize) -> bool {
+        self.flow(program, offset) == Flow::SkipIf(flag)
+            && self.flow(program, offset + 1) == Flow::Goto(self.address + offset + 3)
+    }
+
+    /// `counter += 1`, then jump back to `head` unless `counter > n`, starting at `offset`.
+    fn is_count_up(
+        &self,
+        program: &[Instruction],
+        offset: usize,
+        [counter, n, flag]: [usize; 3],
+        head: usize,
+    ) -> bool {
+        let compare = program[offset + 1];
+        self.is_increment(&program[offset], counter)
+            && compare.opcode == Opcode::gtrr
+            && (compare.input1, compare.input2, compare.output) == (counter, n, flag)
+            && self.flow(program, offset + 2) == Flow::SkipIf(flag)
+            && self.flow(program, offset + 3) == Flow::Goto(self.address + head)
+    }
+
+    fn distinct(&self, registers: &[usize]) -> bool {
+        let mut seen = vec![self.ip];
+        registers.iter().all(|r| {
+            let new = !seen.contains(r);
+            seen.push(*r);
+            new
+        })
+    }
+
+    /// ```text
+    ///  0  a = 1
+    ///  1  b = 1
+    ///  2  flag = a * b == n
+    ///  4  if flag: sum += a
+    ///  7  b += 1, back to 2 unless b > n
+    /// 11  a += 1, back to 1 unless a > n
+    /// ```
+    fn divisor_sum(&self, program: &[Instruction]) -> Option<Native> {
+        if program.len() < 15 {
+            return None;
+        }
+
+        let a = self.set(&program[0], 1)?;
+        let b = self.set(&program[1], 1)?;
+        let flag = program[2].output;
+        let n = self.with(&program[3], Opcode::eqrr, flag)?;
+        let sum = program[6].output;
+
+        let matches = self.with(&program[2], Opcode::mulr, a)? == b
+            && program[3].output == flag
+            && self.is_skip_over(program, 4, flag)
+            && self.with(&program[6], Opcode::addr, a)? == sum
+            && self.is_count_up(program, 7, [b, n, flag], 2)
+            && self.is_count_up(program, 11, [a, n, flag], 1)
+            && self.distinct(&[n, sum, a, b, flag]);
+
+        matches.then_some(Native::DivisorSum {
+            n,
+            sum,
+            a,
+            b,
+            flag,
+            exit: self.address + 15,
+        })
+    }
+
+    /// ```text
+    /// 0  quotient = 0
+    /// 1  flag = (quotient + 1) * divisor > dividend
+    /// 4  if flag: goto exit
+    /// 7  quotient += 1
+    /// 8  goto 1
+    /// ```
+    fn divide(&self, program: &[Instruction]) -> Option<Native> {
+        if program.len() < 9 {
+            return None;
+        }
+
+        let quotient = self.set(&program[0], 0)?;
+        let [next, multiply, compare] = [program[1], program[2], program[3]];
+        let flag = next.output;
+        let divisor = multiply.input2;
+        let dividend = compare.input2;
+        let Flow::Goto(exit) = self.flow(program, 6) else {
+            return None;
+        };
+
+        let matches = next.opcode == Opcode::addi
+            && (next.input1, next.input2) == (quotient, 1)
+            && multiply.opcode == Opcode::muli
+            && (multiply.input1, multiply.output) == (flag, flag)
+            && divisor > 0
+            && compare.opcode == Opcode::gtrr
+            && (compare.input1, compare.output) == (flag, flag)
+            && self.is_skip_over(program, 4, flag)
+            && self.is_increment(&program[7], quotient)
+            && self.flow(program, 8) == Flow::Goto(self.address + 1)
+            && self.distinct(&[quotient, flag, dividend]);
+
+        matches.then_some(Native::Divide {
+            dividend,
+            divisor,
+            quotient,
+            flag,
+            exit,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{divisor_sum, Native};
+    use crate::elfcode::{
+        fixtures::{DIVIDE, DIVISOR_SUM},
+        word::Overflow,
+        Machine,
+    };
+
+    fn run_both(program: &str) -> (Machine<6>, Machine<6>) {
+        let mut plain = Machine::<6>::load(program).unwrap();
+        let mut optimized = plain.clone();
+        assert_eq!(optimized.optimize(), 1);
+
+        plain.run();
+        optimized.run();
+        assert_eq!(plain.registers, optimized.registers);
+        assert!(optimized.executed() < plain.executed());
+        (plain, optimized)
+    }
+
+    #[test]
+    fn sums_divisors() {
+        assert_eq!(divisor_sum(1), 1);
+        assert_eq!(divisor_sum(16), 31);
+
+        let (_, optimized) = run_both(DIVISOR_SUM);
+        assert_eq!(optimized.registers[0], 28);
+    }
+
+    #[test]
+    fn divides() {
+        let (_, optimized) = run_both(DIVIDE);
+        assert_eq!(optimized.registers[3], 3);
+        assert_eq!(
+            optimized.natives().get(&1),
+            Some(&Native::Divide {
+                dividend: 4,
+                divisor: 256,
+                quotient: 3,
+                flag: 2,
+                exit: 10
+            })
+        );
+    }
+
+    #[test]
+    fn follows_the_overflow_policy() {
+        let machine = |overflow| {
+            let mut machine = Machine::<6, i32>::load(DIVISOR_SUM)
+                .unwrap()
+                .with_overflow(overflow);
+            machine.optimize();
+            machine.registers[0] = i32::MAX - 20;
+            machine
+        };
+
+        // the sum of the divisors of 12 is 28
+        let mut wrapping = machine(Overflow::Wrapping);
+        wrapping.run();
+        assert_eq!(wrapping.registers[0], (i32::MAX - 20).wrapping_add(28));
+
+        let mut saturating = machine(Overflow::Saturating);
+        saturating.run();
+        assert_eq!(saturating.registers[0], i32::MAX);
+        assert_eq!(saturating.executed(), wrapping.executed());
+
+        // the loop runs instruction by instruction to find the divisor that overflows
+        let mut checked = machine(Overflow::Checked);
+        let error = checked.try_run().unwrap_err();
+        assert_eq!(error.to_string(), "instruction 07 overflowed: addr 1 0 0");
+        assert_eq!(checked.registers[0], i32::MAX - 20 + 1 + 2 + 3 + 4 + 6);
+        assert_eq!(checked.registers[1], 12);
+    }
+
+    #[test]
+    fn refuses_loops_that_overflow() {
+        let divisor_sum = Native::DivisorSum {
+            n: 0,
+            sum: 1,
+            a: 2,
+            b: 3,
+            flag: 4,
+            exit: 15,
+        };
+        let divide = Native::Divide {
+            dividend: 0,
+            divisor: 256,
+            quotient: 1,
+            flag: 2,
+            exit: 10,
+        };
+
+        // the divisor sum loops multiply 2^16 + 1 by itself
+        let mut registers = [1 << 16, 0, 0, 0, 0u32];
+        assert_eq!(divisor_sum.apply(&mut registers, Overflow::Wrapping), None);
+        assert_eq!(registers, [1 << 16, 0, 0, 0, 0]);
+        let mut registers = [1 << 15, 0, 0, 0, 0u32];
+        assert_eq!(
+            divisor_sum.apply(&mut registers, Overflow::Checked),
+            Some(15)
+        );
+        assert_eq!(registers[1], (1 << 16) - 1);
+
+        // the last (quotient + 1) * divisor is past the largest word
+        let mut registers = [u32::MAX, 0, 0];
+        assert_eq!(divide.apply(&mut registers, Overflow::Saturating), None);
+        let mut registers = [u32::MAX - 256, 0, 0];
+        assert_eq!(divide.apply(&mut registers, Overflow::Checked), Some(10));
+        assert_eq!(registers[1], u32::MAX / 256 - 1);
+    }
+
+    #[test]
+    fn keeps_other_programs() {
+        let mut machine =
+            Machine::<6>::load("#ip 0\nseti 5 0 1\nseti 6 0 2\naddi 0 1 0\n").unwrap();
+        assert_eq!(machine.optimize(), 0);
+    }
+}
@@ -0,0 +1,236 @@
+//! Counts how often each instruction of an ElfCode program runs, to find its hot loops.
+
+use std::{cmp::Reverse, collections::BTreeMap, fmt};
+
+use super::{
+    disasm::{disassemble, flow, Flow, Listing},
+    Instruction, Machine,
+};
+
+/// A loop of the program, with how much of the run it accounts for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HotLoop {
+    pub head: usize,
+    pub tail: usize,
+    /// How often the loop jumped back to its head.
+    pub iterations: usize,
+    /// How many instructions were executed inside the loop, including nested loops.
+    pub executed: usize,
+}
+
+#[derive(Debug)]
+pub struct Profile {
+    ip_binding: Option<usize>,
+    program: Vec<Instruction>,
+    listing: Listing,
+    hits: Vec<usize>,
+    /// How often each instruction continued with the next one.
+    fallthrough: Vec<usize>,
+    /// Jumps that skipped or went back, with how often they were taken.
+    edges: BTreeMap<(usize, usize), usize>,
+    executed: usize,
+    halted: bool,
+}
+
+/// Run the machine until it halts or has executed `limit` instructions, counting what it does.
+pub fn profile<const R: usize>(machine: &mut Machine<R>, limit: usize) -> Profile {
+    let len = machine.program.len();
+    let mut hits = vec![0; len];
+    let mut fallthrough = vec![0; len];
+    let mut edges = BTreeMap::new();
+
+    let start = machine.executed();
+    while machine.executed() - start < limit {
+        let from = machine.ip();
+        if !machine.step() {
+            break;
+        }
+
+        let to = machine.ip();
+        hits[from] += 1;
+        if to == from + 1 {
+            fallthrough[from] += 1;
+        } else {
+            *edges.entry((from, to)).or_insert(0) += 1;
+        }
+    }
+
+    Profile {
+        ip_binding: machine.ip_binding(),
+        program: machine.program.clone(),
+        listing: disassemble(machine.ip_binding(), &machine.program),
+        hits,
+        fallthrough,
+        edges,
+        executed: machine.executed() - start,
+        halted: machine.is_halted(),
+    }
+}
+
+impl Profile {
+    /// How often the instruction at `address` was executed.
+    pub fn hits(&self, address: usize) -> usize {
+        self.hits.get(address).copied().unwrap_or(0)
+    }
+
+    /// How often the jump from one address to another was taken, for every jump that happened.
+    pub fn edges(&self) -> impl Iterator<Item = ((usize, usize), usize)> + '_ {
+        self.edges.iter().map(|(edge, count)| (*edge, *count))
+    }
+
+    pub fn executed(&self) -> usize {
+        self.executed
+    }
+
+    /// The loops of the program that ran, the ones that went around most often first.
+    /// Inner loops come before the outer loops that contain them, which run as long but fewer times.
+    pub fn hot_loops(&self) -> Vec<HotLoop> {
+        let mut loops: Vec<HotLoop> = self
+            .listing
+            .loops()
+            .iter()
+            .map(|l| HotLoop {
+                head: l.head,
+                tail: l.tail,
+                iterations: l
+                    .back_edges
+                    .iter()
+                    .map(|from| self.edges.get(&(*from, l.head)).unwrap_or(&0))
+                    .sum(),
+                executed: self.hits[l.head..=l.tail].iter().sum(),
+            })
+            .filter(|l| l.executed > 0)
+            .collect();
+
+        loops.sort_by_key(|l| (Reverse(l.iterations), Reverse(l.executed)));
+        loops
+    }
+
+    fn percentage(&self, count: usize) -> f64 {
+        if self.executed == 0 {
+            0.0
+        } else {
+            count as f64 * 100.0 / self.executed as f64
+        }
+    }
+
+    /// Describe where the jump at `address` went.
+    fn jumps(&self, address: usize) -> Option<String> {
+        let hits = self.hits[address];
+        match flow(self.ip_binding, address, self.program[address]) {
+            Flow::Next => None,
+            Flow::Goto(_) => Some(format!("taken {hits}")),
+            Flow::SkipIf(_) => Some(format!(
+                "taken {}, not taken {}",
+                hits - self.fallthrough[address],
+                self.fallthrough[address]
+            )),
+            Flow::Computed => {
+                let targets: Vec<String> = self
+                    .edges
+                    .range((address, 0)..=(address, usize::MAX))
+                    .map(|((_, to), count)| format!("{to:02} x{count}"))
+                    .collect();
+                Some(format!("went to {}", targets.join(", ")))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "; executed {} instructions, {}",
+            self.executed,
+            if self.halted {
+                "then the program halted"
+            } else {
+                "then stopped at the limit"
+            }
+        )?;
+        writeln!(f)?;
+
+        for (address, instruction) in self.program.iter().enumerate() {
+            let hits = self.hits[address];
+            let line = format!(
+                "{hits:>12} {:>5.1}%  {address:02}  {:<16}{}",
+                self.percentage(hits),
+                instruction.to_string(),
+                self.listing.code(address).unwrap_or_default()
+            );
+            match self.jumps(address) {
+                Some(jumps) => writeln!(f, "{line:<64} ; {jumps}")?,
+                None => writeln!(f, "{line}")?,
+            }
+        }
+
+        let loops = self.hot_loops();
+        if !loops.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "; hottest loops")?;
+        }
+        for l in loops.iter().take(5) {
+            writeln!(
+                f,
+                ";   {:02}..={:02}: {} iterations, {:.1}% of all instructions",
+                l.head,
+                l.tail,
+                l.iterations,
+                self.percentage(l.executed)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{profile, HotLoop};
+    use crate::elfcode::{fixtures::COUNT, Machine};
+
+    #[test]
+    fn counts_instructions_and_jumps() {
+        let mut machine = Machine::<6>::load(COUNT).unwrap();
+        let profile = profile(&mut machine, 1000);
+
+        assert_eq!(profile.executed(), 1 + 5 * 3 + 4 + 1);
+        assert_eq!(profile.hits(1), 5);
+        assert_eq!(profile.hits(4), 4);
+        assert_eq!(
+            profile.edges().collect::<Vec<_>>(),
+            vec![((3, 5), 1), ((4, 1), 4), ((5, 26), 1)]
+        );
+        assert_eq!(
+            profile.hot_loops(),
+            vec![HotLoop {
+                head: 1,
+                tail: 4,
+                iterations: 4,
+                executed: 19
+            }]
+        );
+
+        let text = profile.to_string();
+        assert!(text.starts_with("; executed 21 instructions, then the program halted"));
+        assert!(
+            text.contains("addr 1 4 4      if r1 goto L05")
+                && text.contains("; taken 1, not taken 4")
+        );
+        assert!(text.contains(";   01..=04: 4 iterations, 90.5% of all instructions"));
+    }
+
+    #[test]
+    fn stops_at_the_limit() {
+        let mut machine = Machine::<2>::load("#ip 1\nseti 0 0 0\nseti 0 0 1\n").unwrap();
+        let profile = profile(&mut machine, 10);
+        assert_eq!(profile.executed(), 10);
+        assert!(profile.to_string().contains("then stopped at the limit"));
+        // the second instruction jumps to itself
+        assert_eq!(profile.hits(1), 9);
+        assert_eq!(profile.hot_loops()[0].iterations, 9);
+    }
+}
@@ -0,0 +1,131 @@
+//! The types ElfCode registers can hold, and what happens when arithmetic overflows them.
+//!
+//! Plain `+` and `*` panic on overflow in debug builds and wrap in release builds, so the VM
+//! goes through [`Word`] instead, which makes the outcome the same in every build.
+
+use std::{
+    error::Error,
+    fmt::{self, Debug, Display},
+    hash::Hash,
+};
+
+use super::Instruction;
+
+/// What to do when `add` or `mul` overflows a register.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Overflow {
+    /// Wrap around, like the machine words of real hardware.
+    #[default]
+    Wrapping,
+    /// Stop the machine with an [`OverflowError`].
+    Checked,
+    /// Stay at the largest or smallest value of the word.
+    Saturating,
+}
+
+/// A value that fits in a register.
+pub trait Word: Copy + Default + Eq + Ord + Hash + Debug + Display + 'static {
+    const ONE: Self;
+
+    /// Convert an immediate or an address, wrapping like `as` does.
+    fn from_usize(value: usize) -> Self;
+
+    /// The value as an address, or `None` if it is negative or too large.
+    fn to_usize(self) -> Option<usize>;
+
+    /// Add, returning `None` only if it overflows while `overflow` is [`Overflow::Checked`].
+    fn add(self, other: Self, overflow: Overflow) -> Option<Self>;
+
+    /// Multiply, returning `None` only if it overflows while `overflow` is [`Overflow::Checked`].
+    fn mul(self, other: Self, overflow: Overflow) -> Option<Self>;
+
+    fn and(self, other: Self) -> Self;
+
+    fn or(self, other: Self) -> Self;
+
+    fn from_bool(value: bool) -> Self {
+        Self::from_usize(value as usize)
+    }
+}
+
+macro_rules! impl_word {
+    ($($t:ty),*) => {$(
+        impl Word for $t {
+            const ONE: Self = 1;
+
+            fn from_usize(value: usize) -> Self {
+                value as $t
+            }
+
+            fn to_usize(self) -> Option<usize> {
+                usize::try_from(self).ok()
+            }
+
+            fn add(self, other: Self, overflow: Overflow) -> Option<Self> {
+                match overflow {
+                    Overflow::Wrapping => Some(self.wrapping_add(other)),
+                    Overflow::Checked => self.checked_add(other),
+                    Overflow::Saturating => Some(self.saturating_add(other)),
+                }
+            }
+
+            fn mul(self, other: Self, overflow: Overflow) -> Option<Self> {
+                match overflow {
+                    Overflow::Wrapping => Some(self.wrapping_mul(other)),
+                    Overflow::Checked => self.checked_mul(other),
+                    Overflow::Saturating => Some(self.saturating_mul(other)),
+                }
+            }
+
+            fn and(self, other: Self) -> Self {
+                self & other
+            }
+
+            fn or(self, other: Self) -> Self {
+                self | other
+            }
+        }
+    )*};
+}
+
+impl_word!(u32, u64, usize, i32, i64);
+
+/// An instruction overflowed a register while the machine checks for overflows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverflowError {
+    /// The address of the instruction.
+    pub address: usize,
+    pub instruction: Instruction,
+}
+
+impl Error for OverflowError {}
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "instruction {:02} overflowed: {}",
+            self.address, self.instruction
+        )
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Overflow, Word};
+
+    #[test]
+    fn follows_the_overflow_policy() {
+        assert_eq!(u32::MAX.add(2, Overflow::Wrapping), Some(1));
+        assert_eq!(u32::MAX.add(2, Overflow::Checked), None);
+        assert_eq!(u32::MAX.add(2, Overflow::Saturating), Some(u32::MAX));
+        assert_eq!(i64::MIN.mul(2, Overflow::Saturating), Some(i64::MIN));
+        assert_eq!(3i64.mul(-2, Overflow::Checked), Some(-6));
+
+        assert_eq!((-1i64).to_usize(), None);
+        assert_eq!(u32::from_usize(1 << 32 | 7), 7);
+        assert_eq!(i64::from_bool(true), 1);
+    }
+}
@@ -0,0 +1,388 @@
+use std::{
+    error::Error,
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use crate::Coordinate;
+
+/// A dense, rectangular grid of cells, stored row by row in a `Vec`.
+///
+/// Cells are addressed with `Coordinate { left, top }`, where `left` is the column and `top` the row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+/// An error which can be returned when parsing a [`Grid`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum GridError {
+    /// A row has a different length than the first row.
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl Error for GridError {}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GridError::RaggedRow {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {row} has {found} cells, expected {expected} like the first row"
+            ),
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Create a grid where every cell has the same value.
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Create a grid from cells in row order.
+    ///
+    /// # Panics
+    /// If the number of cells is not a multiple of `width`.
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Self {
+        assert!(
+            width > 0 && cells.len().is_multiple_of(width),
+            "cells do not fill a grid of width {width}"
+        );
+        Grid {
+            width,
+            height: cells.len() / width,
+            cells,
+        }
+    }
+
+    /// Parse a character map, mapping every character to a cell.
+    pub fn parse(input: &str, mut f: impl FnMut(char) -> T) -> Result<Self, GridError> {
+        let mut width = None;
+        let mut cells = vec![];
+
+        for (row, line) in input.lines().enumerate() {
+            let len = cells.len();
+            cells.extend(line.chars().map(&mut f));
+            let found = cells.len() - len;
+
+            match width {
+                None => width = Some(found),
+                Some(expected) if expected != found => {
+                    return Err(GridError::RaggedRow {
+                        row,
+                        expected,
+                        found,
+                    })
+                }
+                _ => {}
+            }
+        }
+
+        let width = width.unwrap_or(0);
+        Ok(Grid {
+            width,
+            height: cells.len().checked_div(width).unwrap_or(0),
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, c: Coordinate<usize>) -> bool {
+        c.left < self.width && c.top < self.height
+    }
+
+    fn index_of(&self, c: Coordinate<usize>) -> Option<usize> {
+        self.contains(c).then_some(c.top * self.width + c.left)
+    }
+
+    fn coordinate_of(&self, index: usize) -> Coordinate<usize> {
+        Coordinate {
+            left: index % self.width,
+            top: index / self.width,
+        }
+    }
+
+    pub fn get(&self, c: Coordinate<usize>) -> Option<&T> {
+        self.index_of(c).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, c: Coordinate<usize>) -> Option<&mut T> {
+        self.index_of(c).map(|i| &mut self.cells[i])
+    }
+
+    /// Set a cell. Returns false, and leaves the grid unchanged, if `c` is out of bounds.
+    pub fn set(&mut self, c: Coordinate<usize>, value: T) -> bool {
+        match self.get_mut(c) {
+            Some(cell) => {
+                *cell = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The in-bounds orthogonal neighbours of a cell, in reading order.
+    pub fn neighbours_4(
+        &self,
+        c: Coordinate<usize>,
+    ) -> impl Iterator<Item = Coordinate<usize>> + '_ {
+        c.neighbours_4().filter(|n| self.contains(*n))
+    }
+
+    /// The in-bounds orthogonal and diagonal neighbours of a cell, in reading order.
+    pub fn neighbours_8(
+        &self,
+        c: Coordinate<usize>,
+    ) -> impl Iterator<Item = Coordinate<usize>> + '_ {
+        c.neighbours_8().filter(|n| self.contains(*n))
+    }
+
+    /// All cells with their coordinates, in reading order.
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinate<usize>, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| (self.coordinate_of(i), cell))
+    }
+
+    /// All cells, in reading order.
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn row(&self, top: usize) -> Option<&[T]> {
+        (top < self.height).then(|| &self.cells[top * self.width..(top + 1) * self.width])
+    }
+
+    pub fn column(&self, left: usize) -> impl Iterator<Item = &T> {
+        let cells = if left < self.width {
+            &self.cells[left..]
+        } else {
+            &[]
+        };
+        cells.iter().step_by(self.width.max(1))
+    }
+
+    /// Create a grid of the same size by mapping every cell.
+    pub fn map<U>(&self, mut f: impl FnMut(Coordinate<usize>, &T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.iter().map(|(c, cell)| f(c, cell)).collect(),
+        }
+    }
+
+    /// A view of the sub-grid with its top left corner at `origin`, or `None` if it does not fit.
+    pub fn view(
+        &self,
+        origin: Coordinate<usize>,
+        width: usize,
+        height: usize,
+    ) -> Option<GridView<'_, T>> {
+        (origin.left + width <= self.width && origin.top + height <= self.height).then_some(
+            GridView {
+                grid: self,
+                origin,
+                width,
+                height,
+            },
+        )
+    }
+}
+
+impl<T> Index<Coordinate<usize>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, c: Coordinate<usize>) -> &T {
+        self.get(c).expect("coordinate out of bounds")
+    }
+}
+
+impl<T> IndexMut<Coordinate<usize>> for Grid<T> {
+    fn index_mut(&mut self, c: Coordinate<usize>) -> &mut T {
+        self.get_mut(c).expect("coordinate out of bounds")
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.view(Coordinate { left: 0, top: 0 }, self.width, self.height) {
+            Some(view) => view.fmt(f),
+            None => Ok(()),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A borrowed, rectangular part of a [`Grid`]. Coordinates are relative to the view.
+#[derive(Debug, Clone, Copy)]
+pub struct GridView<'a, T> {
+    grid: &'a Grid<T>,
+    origin: Coordinate<usize>,
+    width: usize,
+    height: usize,
+}
+
+impl<'a, T> GridView<'a, T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, c: Coordinate<usize>) -> Option<&'a T> {
+        if c.left >= self.width || c.top >= self.height {
+            return None;
+        }
+        self.grid.get(Coordinate {
+            left: self.origin.left + c.left,
+            top: self.origin.top + c.top,
+        })
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [T]> + '_ {
+        (0..self.height).map(move |top| {
+            let row = self.grid.row(self.origin.top + top).unwrap_or_default();
+            &row[self.origin.left..self.origin.left + self.width]
+        })
+    }
+
+    /// All cells with their coordinates relative to the view, in reading order.
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinate<usize>, &'a T)> + '_ {
+        self.rows().enumerate().flat_map(|(top, row)| {
+            row.iter()
+                .enumerate()
+                .map(move |(left, cell)| (Coordinate { left, top }, cell))
+        })
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for GridView<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Grid, GridError};
+    use crate::Coordinate;
+
+    fn c(left: usize, top: usize) -> Coordinate<usize> {
+        Coordinate { left, top }
+    }
+
+    fn get_mock_grid() -> Grid<char> {
+        Grid::parse("#..\n.#.\n..#\n.##", |c| c).unwrap()
+    }
+
+    #[test]
+    fn parses_character_maps() {
+        let grid = Grid::parse("#.\n.#", |c| c == '#').unwrap();
+        assert_eq!(grid.width(), 2);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid.get(c(1, 1)), Some(&true));
+        assert_eq!(grid.get(c(1, 0)), Some(&false));
+        assert_eq!(
+            Grid::parse("#.\n#", |c| c),
+            Err(GridError::RaggedRow {
+                row: 1,
+                expected: 2,
+                found: 1
+            })
+        );
+    }
+
+    #[test]
+    fn checks_bounds() {
+        let mut grid = get_mock_grid();
+        assert_eq!(grid.get(c(3, 0)), None);
+        assert_eq!(grid.get(c(0, 4)), None);
+        assert!(grid.set(c(2, 3), 'x'));
+        assert!(!grid.set(c(3, 3), 'x'));
+        assert_eq!(grid[c(2, 3)], 'x');
+    }
+
+    #[test]
+    fn iterates_neighbours() {
+        let grid = get_mock_grid();
+        assert_eq!(
+            grid.neighbours_4(c(0, 0)).collect::<Vec<_>>(),
+            vec![c(1, 0), c(0, 1)]
+        );
+        assert_eq!(grid.neighbours_8(c(0, 0)).count(), 3);
+        assert_eq!(grid.neighbours_8(c(1, 1)).count(), 8);
+        assert_eq!(grid.neighbours_8(c(2, 3)).count(), 3);
+    }
+
+    #[test]
+    fn iterates_rows_and_columns() {
+        let grid = get_mock_grid();
+        assert_eq!(grid.row(1), Some(&['.', '#', '.'][..]));
+        assert_eq!(grid.row(4), None);
+        assert_eq!(grid.rows().count(), 4);
+        assert_eq!(grid.column(2).collect::<String>(), "..##");
+        assert_eq!(grid.column(3).count(), 0);
+    }
+
+    #[test]
+    fn views_sub_grids() {
+        let grid = get_mock_grid();
+        let view = grid.view(c(1, 1), 2, 3).unwrap();
+        assert_eq!(view.to_string(), "#.\n.#\n##");
+        assert_eq!(view.get(c(0, 0)), Some(&'#'));
+        assert_eq!(view.get(c(2, 0)), None);
+        assert_eq!(view.iter().filter(|(_, c)| **c == '#').count(), 4);
+        assert!(grid.view(c(1, 1), 3, 1).is_none());
+    }
+
+    #[test]
+    fn formats_grids() {
+        let grid = get_mock_grid();
+        assert_eq!(grid.to_string(), "#..\n.#.\n..#\n.##");
+        let mapped = grid.map(|c, cell| if c.top == 0 { '?' } else { *cell });
+        assert_eq!(mapped.to_string(), "???\n.#.\n..#\n.##");
+    }
+}
@@ -1,79 +1,170 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
+use advent_of_code::template::commands::{
+    all, disasm, download, read, scaffold, solve, time, translate,
+};
 use args::{parse, AppArguments};
 
+#[cfg(feature = "serve")]
+use advent_of_code::template::commands::serve;
 #[cfg(feature = "today")]
 use advent_of_code::template::Day;
+use advent_of_code::template::Puzzle;
 #[cfg(feature = "today")]
 use std::process;
 
+/// Every solution of the repository, compiled in-process. Generated by `build.rs`.
+#[cfg(feature = "serve")]
+// solutions that already return an `Answer` are converted, too.
+#[allow(clippy::useless_conversion)]
+mod solutions {
+    use advent_of_code::template::commands::serve::Solution;
+
+    include!(concat!(env!("OUT_DIR"), "/solutions.rs"));
+}
+
 mod args {
-    use advent_of_code::template::Day;
+    use advent_of_code::template::{
+        commands::solve::VmMode, config::config, report::Report, Day, Year,
+    };
     use std::process;
 
     pub enum AppArguments {
         Download {
+            year: Option<Year>,
             day: Day,
         },
         Read {
+            year: Option<Year>,
+            day: Day,
+        },
+        Grep {
+            pattern: String,
+        },
+        Disasm {
+            year: Option<Year>,
             day: Day,
         },
+        Translate {
+            year: Option<Year>,
+            day: Day,
+            samples: Day,
+        },
         Scaffold {
+            year: Option<Year>,
             day: Day,
             download: bool,
         },
         Solve {
+            year: Option<Year>,
             day: Day,
             release: bool,
             dhat: bool,
             submit: Option<u8>,
+            vm: Option<VmMode>,
         },
         All {
+            year: Option<Year>,
             release: bool,
+            reports: Vec<Report>,
         },
         Time {
+            year: Option<Year>,
             all: bool,
             day: Option<Day>,
             store: bool,
+            reports: Vec<Report>,
         },
         #[cfg(feature = "today")]
-        Today,
+        Today {
+            year: Option<Year>,
+        },
+        #[cfg(feature = "serve")]
+        Serve {
+            year: Option<Year>,
+            port: u16,
+            timeout: u64,
+        },
     }
 
     pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
         let mut args = pico_args::Arguments::from_env();
 
-        let app_args = match args.subcommand()?.as_deref() {
+        let subcommand = args.subcommand()?;
+        // every subcommand accepts `--year`, defaulting to the configured year.
+        let year = args
+            .opt_value_from_str::<_, Year>("--year")?
+            .or(config().year);
+
+        let app_args = match subcommand.as_deref() {
             Some("all") => AppArguments::All {
-                release: args.contains("--release"),
+                year,
+                release: args.contains("--release") || config().release,
+                reports: args.values_from_str("--report")?,
             },
             Some("time") => {
-                let all = args.contains("--all");
-                let store = args.contains("--store");
+                let all = args.contains("--all") || config().time_all;
+                let store = args.contains("--store") || config().time_store;
+                let reports = args.values_from_str("--report")?;
 
                 AppArguments::Time {
+                    year,
                     all,
                     day: args.opt_free_from_str()?,
                     store,
+                    reports,
                 }
             }
             Some("download") => AppArguments::Download {
+                year,
+                day: args.free_from_str()?,
+            },
+            Some("read") => match args.opt_value_from_str("--grep")? {
+                Some(pattern) => AppArguments::Grep { pattern },
+                None => AppArguments::Read {
+                    year,
+                    day: args.free_from_str()?,
+                },
+            },
+            Some("disasm") => AppArguments::Disasm {
+                year,
                 day: args.free_from_str()?,
             },
-            Some("read") => AppArguments::Read {
+            Some("translate") => AppArguments::Translate {
+                year,
+                samples: args
+                    .opt_value_from_str("--samples")?
+                    .unwrap_or(advent_of_code::day!(16)),
                 day: args.free_from_str()?,
             },
             Some("scaffold") => AppArguments::Scaffold {
+                year,
                 day: args.free_from_str()?,
                 download: args.contains("--download"),
             },
             Some("solve") => AppArguments::Solve {
+                year,
                 day: args.free_from_str()?,
-                release: args.contains("--release"),
+                release: args.contains("--release") || config().release,
                 submit: args.opt_value_from_str("--submit")?,
                 dhat: args.contains("--dhat"),
+                vm: if args.contains("--debug-vm") {
+                    Some(VmMode::Debug)
+                } else if args.contains("--profile-vm") {
+                    Some(VmMode::Profile {
+                        limit: args.opt_value_from_str("--limit")?.unwrap_or(10_000_000),
+                        r0: args.opt_value_from_str("--r0")?.unwrap_or(0),
+                    })
+                } else {
+                    None
+                },
             },
             #[cfg(feature = "today")]
-            Some("today") => AppArguments::Today,
+            Some("today") => AppArguments::Today { year },
+            #[cfg(feature = "serve")]
+            Some("serve") => AppArguments::Serve {
+                year,
+                port: args.opt_value_from_str("--port")?.unwrap_or(8080),
+                timeout: args.opt_value_from_str("--timeout")?.unwrap_or(10),
+            },
             Some(x) => {
                 eprintln!("Unknown command: {x}");
                 process::exit(1);
@@ -100,29 +191,63 @@ fn main() {
             std::process::exit(1);
         }
         Ok(args) => match args {
-            AppArguments::All { release } => all::handle(release),
-            AppArguments::Time { day, all, store } => time::handle(day, all, store),
-            AppArguments::Download { day } => download::handle(day),
-            AppArguments::Read { day } => read::handle(day),
-            AppArguments::Scaffold { day, download } => {
-                scaffold::handle(day);
+            AppArguments::All {
+                year,
+                release,
+                reports,
+            } => all::handle(year, release, &reports),
+            AppArguments::Time {
+                year,
+                day,
+                all,
+                store,
+                reports,
+            } => time::handle(year, day, all, store, &reports),
+            AppArguments::Download { year, day } => download::handle(Puzzle::new(year, day)),
+            AppArguments::Read { year, day } => read::handle(Puzzle::new(year, day)),
+            AppArguments::Grep { pattern } => read::grep(&pattern),
+            AppArguments::Disasm { year, day } => disasm::handle(Puzzle::new(year, day)),
+            AppArguments::Translate { year, day, samples } => {
+                translate::handle(Puzzle::new(year, day), Puzzle::new(year, samples))
+            }
+            AppArguments::Scaffold {
+                year,
+                day,
+                download,
+            } => {
+                let puzzle = Puzzle::new(year, day);
+                scaffold::handle(puzzle);
                 if download {
-                    download::handle(day);
+                    download::handle(puzzle);
                 }
             }
             AppArguments::Solve {
+                year,
                 day,
                 release,
                 dhat,
                 submit,
-            } => solve::handle(day, release, dhat, submit),
+                vm,
+            } => solve::handle(Puzzle::new(year, day), release, dhat, submit, vm),
+            #[cfg(feature = "serve")]
+            AppArguments::Serve {
+                year,
+                port,
+                timeout,
+            } => serve::handle(
+                year,
+                port,
+                std::time::Duration::from_secs(timeout),
+                solutions::SOLUTIONS,
+            ),
             #[cfg(feature = "today")]
-            AppArguments::Today => {
+            AppArguments::Today { year } => {
                 match Day::today() {
                     Some(day) => {
-                        scaffold::handle(day);
-                        download::handle(day);
-                        read::handle(day)
+                        let puzzle = Puzzle::new(year, day);
+                        scaffold::handle(puzzle);
+                        download::handle(puzzle);
+                        read::handle(puzzle)
                     }
                     None => {
                         eprintln!(
@@ -0,0 +1,263 @@
+use std::{borrow::Cow, error::Error, fmt, str::FromStr};
+
+use nom::{
+    bytes::complete::tag,
+    character::complete::{char, digit1, line_ending, one_of, space0},
+    combinator::{map_res, opt, recognize},
+    error::ErrorKind,
+    multi::{many1, separated_list0, separated_list1},
+    sequence::{delimited, pair, preceded, separated_pair, tuple},
+    Err, IResult,
+};
+
+/// An error which can be returned by [`parse_all`], pointing at where the input stopped making sense.
+#[derive(PartialEq, Eq)]
+pub struct ParseError {
+    /// The line of the error, starting at 1.
+    pub line: usize,
+    /// The column of the error in characters, starting at 1.
+    pub column: usize,
+    /// The offending line, without its line ending.
+    pub text: String,
+    /// A description of what the parser expected to find.
+    pub expected: String,
+}
+
+impl Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let gutter = " ".repeat(self.line.to_string().len());
+        writeln!(
+            f,
+            "line {}, column {}: expected {}",
+            self.line, self.column, self.expected
+        )?;
+        writeln!(f, "{} | {}", self.line, self.text)?;
+        write!(f, "{gutter} | {}^", " ".repeat(self.column - 1))
+    }
+}
+
+// solutions unwrap the result, so make the panic message as readable as the error itself.
+impl fmt::Debug for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f)?;
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl ParseError {
+    /// Build an error for the position where `rest` starts in `input`.
+    fn at(input: &str, rest: &str, expected: impl Into<String>) -> Self {
+        let offset = input.len() - rest.len();
+        let start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+
+        ParseError {
+            line: input[..offset].matches('\n').count() + 1,
+            column: input[start..offset].chars().count() + 1,
+            text: input[start..end].to_string(),
+            expected: expected.into(),
+        }
+    }
+}
+
+fn describe(kind: ErrorKind) -> String {
+    match kind {
+        ErrorKind::Eof => "end of input".into(),
+        ErrorKind::Digit => "a number".into(),
+        ErrorKind::Alpha => "a letter".into(),
+        ErrorKind::AlphaNumeric => "a letter or digit".into(),
+        ErrorKind::Space | ErrorKind::MultiSpace => "whitespace".into(),
+        ErrorKind::CrLf => "a line ending".into(),
+        kind => format!("{} token", kind.description().to_lowercase()),
+    }
+}
+
+/// Run `parser` over the whole input.
+///
+/// Windows line endings are converted to `\n` first. Apart from trailing whitespace,
+/// the parser has to consume all input; leftovers are reported as an error too.
+///
+/// The output may borrow from `input`. When the line endings are converted, the converted
+/// copy is leaked so that it lives as long as `input` would have.
+pub fn parse_all<'a, O>(
+    mut parser: impl FnMut(&'a str) -> IResult<&'a str, O>,
+    input: &'a str,
+) -> Result<O, ParseError> {
+    let input: &'a str = match normalize(input) {
+        Cow::Borrowed(input) => input,
+        Cow::Owned(input) => Box::leak(input.into_boxed_str()),
+    };
+
+    match parser(input) {
+        Ok((rest, output)) if rest.trim().is_empty() => Ok(output),
+        Ok((rest, _)) => Err(ParseError::at(input, rest, "end of input")),
+        Err(Err::Error(e) | Err::Failure(e)) => {
+            Err(ParseError::at(input, e.input, describe(e.code)))
+        }
+        Err(Err::Incomplete(_)) => Err(ParseError::at(input, "", "more input")),
+    }
+}
+
+fn normalize(input: &str) -> Cow<'_, str> {
+    if input.contains('\r') {
+        Cow::Owned(input.replace("\r\n", "\n"))
+    } else {
+        Cow::Borrowed(input)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Parse an unsigned integer of any type, e.g. `unsigned::<u64>()`.
+pub fn unsigned<'a, T: FromStr>() -> impl FnMut(&'a str) -> IResult<&'a str, T> {
+    map_res(digit1, T::from_str)
+}
+
+/// Parse an integer with an optional `+` or `-` sign, e.g. `signed::<i64>()`.
+pub fn signed<'a, T: FromStr>() -> impl FnMut(&'a str) -> IResult<&'a str, T> {
+    map_res(recognize(pair(opt(one_of("+-")), digit1)), T::from_str)
+}
+
+/// Parse one item per line.
+pub fn lines<'a, O>(
+    parser: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    separated_list0(line_ending, parser)
+}
+
+/// Parse blocks that are separated by one or more blank lines.
+pub fn blocks<'a, O>(
+    parser: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    separated_list1(pair(line_ending, many1(line_ending)), parser)
+}
+
+/// Parse `key: value` into a pair, with optional spaces around the colon.
+pub fn key_value<'a, K, V>(
+    key: impl FnMut(&'a str) -> IResult<&'a str, K>,
+    value: impl FnMut(&'a str) -> IResult<&'a str, V>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (K, V)> {
+    separated_pair(key, delimited(space0, char(':'), space0), value)
+}
+
+/// Parse the value of `label: value`, e.g. `labelled("depth", unsigned::<u32>())`.
+pub fn labelled<'a, O>(
+    label: &'a str,
+    value: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O> {
+    preceded(tuple((tag(label), space0, char(':'), space0)), value)
+}
+
+/// Parse two comma-separated values, allowing spaces before each value and the comma.
+///
+/// Accepts `1,2` as well as `< 1,  -2>`-style padding once the delimiters are taken care of.
+pub fn comma_pair<'a, A, B>(
+    first: impl FnMut(&'a str) -> IResult<&'a str, A>,
+    second: impl FnMut(&'a str) -> IResult<&'a str, B>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (A, B)> {
+    separated_pair(
+        preceded(space0, first),
+        pair(space0, char(',')),
+        preceded(space0, second),
+    )
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use nom::{
+        bytes::complete::tag,
+        character::complete::{alpha1, newline, u32},
+        multi::separated_list1,
+        sequence::separated_pair,
+        IResult,
+    };
+
+    use super::{
+        blocks, comma_pair, key_value, labelled, lines, parse_all, signed, unsigned, ParseError,
+    };
+
+    fn pairs(i: &str) -> IResult<&str, Vec<(u32, u32)>> {
+        separated_list1(newline, separated_pair(u32, tag(","), u32))(i)
+    }
+
+    #[test]
+    fn consumes_all_input() {
+        assert_eq!(parse_all(pairs, "1,2\n3,4\n\n"), Ok(vec![(1, 2), (3, 4)]));
+        assert_eq!(parse_all(pairs, "1,2\r\n3,4\r\n"), Ok(vec![(1, 2), (3, 4)]));
+
+        // outputs can borrow from the input, with either line ending
+        let words = |input| parse_all(lines(alpha1), input);
+        assert_eq!(words("ab\ncd"), Ok(vec!["ab", "cd"]));
+        assert_eq!(words("ab\r\ncd\r\n"), Ok(vec!["ab", "cd"]));
+    }
+
+    #[test]
+    fn reports_leftover_input() {
+        assert_eq!(
+            parse_all(pairs, "1,2\n3,4x\n5,6"),
+            Err(ParseError {
+                line: 2,
+                column: 4,
+                text: "3,4x".into(),
+                expected: "end of input".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn reports_parser_errors() {
+        let error = parse_all(|i| separated_pair(u32, tag(","), u32)(i), "12,ab").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 4));
+        assert_eq!(error.expected, "a number");
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 4: expected a number\n1 | 12,ab\n  |    ^"
+        );
+    }
+
+    #[test]
+    fn parses_integers() {
+        assert_eq!(unsigned::<u64>()("12345678901 "), Ok((" ", 12345678901)));
+        assert!(unsigned::<u8>()("256").is_err());
+        assert!(unsigned::<u32>()("-1").is_err());
+        assert_eq!(signed::<i64>()("-42,"), Ok((",", -42)));
+        assert_eq!(signed::<i8>()("+7"), Ok(("", 7)));
+    }
+
+    #[test]
+    fn parses_lines_and_blocks() {
+        assert_eq!(
+            parse_all(|i| lines(signed::<i32>())(i), "+1\n-2\n3\n"),
+            Ok(vec![1, -2, 3])
+        );
+        assert_eq!(
+            parse_all(|i| blocks(lines(unsigned::<u32>()))(i), "1\n2\n\n3\n\n\n4"),
+            Ok(vec![vec![1, 2], vec![3], vec![4]])
+        );
+    }
+
+    #[test]
+    fn parses_labels_and_pairs() {
+        assert_eq!(
+            key_value(alpha1, unsigned::<u32>())("depth: 510"),
+            Ok(("", ("depth", 510)))
+        );
+        assert_eq!(
+            parse_all(
+                |i| labelled("target", comma_pair(unsigned(), unsigned()))(i),
+                "target : 10,10"
+            ),
+            Ok((10_u32, 10_u32))
+        );
+        assert_eq!(
+            comma_pair(signed::<i32>(), signed::<i32>())(" 9,  -1>"),
+            Ok((">", (9, -1)))
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use std::{
+    fmt,
+    ops::{Add, Index, IndexMut, Sub},
+    str::FromStr,
+};
+
+use nom::{
+    bytes::complete::tag, character::complete::multispace0, combinator::map_opt,
+    multi::separated_list1, sequence::preceded, IResult,
+};
+
+use crate::{coordinate::Integer, parse::signed};
+
+/// A point in `N` dimensions, e.g. `Point<i32, 3>` for a position in space.
+///
+/// Use [`Coordinate`](crate::Coordinate) for points on a 2D grid.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub struct Point<T, const N: usize>(pub [T; N]);
+
+impl<T, const N: usize> Point<T, N> {
+    pub const fn new(values: [T; N]) -> Self {
+        Point(values)
+    }
+
+    /// Parse comma-separated values, e.g. `-1,2,3` into a `Point<i32, 3>`.
+    /// Fails if the number of values is not `N`.
+    pub fn parse(i: &str) -> IResult<&str, Self>
+    where
+        T: FromStr,
+    {
+        map_opt(
+            separated_list1(tag(","), preceded(multispace0, signed())),
+            |values: Vec<T>| values.try_into().ok().map(Point),
+        )(i)
+    }
+}
+
+impl<T: Integer, const N: usize> Point<T, N> {
+    /// The origin, with every value set to zero.
+    pub fn origin() -> Self {
+        Point([T::ZERO; N])
+    }
+
+    /// The Manhattan, or taxicab, distance between two points.
+    pub fn manhattan(self, other: Self) -> T {
+        self.0
+            .iter()
+            .zip(other.0)
+            .fold(T::ZERO, |sum, (&a, b)| sum + a.abs_diff(b))
+    }
+
+    /// The `2 * N` orthogonal neighbours, skipping those that do not fit in `T`.
+    pub fn neighbours(self) -> impl Iterator<Item = Self> {
+        (0..N).flat_map(move |axis| {
+            let value = self.0[axis];
+            [value.checked_sub(T::ONE), value.checked_add(T::ONE)]
+                .into_iter()
+                .flatten()
+                .map(move |value| {
+                    let mut neighbour = self;
+                    neighbour.0[axis] = value;
+                    neighbour
+                })
+        })
+    }
+
+    /// The smallest box containing all points, as its minimum and maximum corner.
+    /// Returns `None` if there are no points.
+    pub fn bounding_box(points: impl IntoIterator<Item = Self>) -> Option<(Self, Self)> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold((first, first), |(mut min, mut max), point| {
+            for axis in 0..N {
+                min.0[axis] = min.0[axis].min(point.0[axis]);
+                max.0[axis] = max.0[axis].max(point.0[axis]);
+            }
+            (min, max)
+        }))
+    }
+
+    /// Returns true if the point lies in the box from `min` to `max`, both inclusive.
+    pub fn within(self, min: Self, max: Self) -> bool {
+        (0..N).all(|axis| min.0[axis] <= self.0[axis] && self.0[axis] <= max.0[axis])
+    }
+}
+
+impl<T, const N: usize> Index<usize> for Point<T, N> {
+    type Output = T;
+
+    fn index(&self, axis: usize) -> &T {
+        &self.0[axis]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for Point<T, N> {
+    fn index_mut(&mut self, axis: usize) -> &mut T {
+        &mut self.0[axis]
+    }
+}
+
+impl<T: fmt::Display, const N: usize> fmt::Display for Point<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (axis, value) in self.0.iter().enumerate() {
+            if axis > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{value}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Integer, const N: usize> Add for Point<T, N> {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        for axis in 0..N {
+            self.0[axis] = self.0[axis] + other.0[axis];
+        }
+        self
+    }
+}
+
+impl<T: Integer, const N: usize> Sub for Point<T, N> {
+    type Output = Self;
+
+    fn sub(mut self, other: Self) -> Self {
+        for axis in 0..N {
+            self.0[axis] = self.0[axis] - other.0[axis];
+        }
+        self
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Point;
+
+    #[test]
+    fn parses_points_of_any_arity() {
+        assert_eq!(
+            Point::<i32, 4>::parse("-1,2,2,0\n"),
+            Ok(("\n", Point([-1, 2, 2, 0])))
+        );
+        assert_eq!(
+            Point::<i64, 3>::parse("10, -12,9>"),
+            Ok((">", Point([10, -12, 9])))
+        );
+        assert!(Point::<i32, 3>::parse("1,2").is_err());
+        assert!(Point::<u8, 2>::parse("-1,2").is_err());
+    }
+
+    #[test]
+    fn measures_distances() {
+        let a = Point([0, 0, 0, 0]);
+        let b = Point([3, 0, -2, 1]);
+        assert_eq!(a.manhattan(b), 6);
+        assert_eq!(b - b, Point::origin());
+        assert_eq!(a + b, b);
+        assert_eq!(b.to_string(), "3,0,-2,1");
+    }
+
+    #[test]
+    fn finds_neighbours_and_bounds() {
+        assert_eq!(Point([0_i32, 0, 0]).neighbours().count(), 6);
+        assert_eq!(
+            Point([0_usize, 5]).neighbours().collect::<Vec<_>>(),
+            vec![Point([1, 5]), Point([0, 4]), Point([0, 6])]
+        );
+
+        let points = [Point([1, -4, 2]), Point([-3, 0, 7]), Point([2, 1, 0])];
+        let (min, max) = Point::bounding_box(points).unwrap();
+        assert_eq!((min, max), (Point([-3, -4, 0]), Point([2, 1, 7])));
+        assert!(points.iter().all(|p| p.within(min, max)));
+        assert!(!Point([3, 0, 0]).within(min, max));
+        assert_eq!(Point::<i32, 3>::bounding_box([]), None);
+    }
+}
@@ -0,0 +1,252 @@
+use std::{collections::HashMap, fs, io::Error, path::Path, str::FromStr};
+use tinyjson::JsonValue;
+
+use crate::template::{
+    config::config,
+    fingerprint::{stale_changes, Fingerprint},
+    Day, Year,
+};
+
+/// Represents the known, correct answers for a single day.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StoredAnswer {
+    pub day: Day,
+    pub part_1: Option<String>,
+    pub part_2: Option<String>,
+    /// The input and source the answers were recorded with.
+    pub fingerprint: Option<Fingerprint>,
+}
+
+/// Represents known answers for a set of days.
+/// Can be serialized from JSON.
+#[derive(Clone, Debug, Default)]
+pub struct Answers {
+    pub data: Vec<StoredAnswer>,
+}
+
+impl Answers {
+    /// Rehydrate answers of a year from a JSON file. If not present, returns empty answers.
+    pub fn read_from_file(year: Option<Year>) -> Self {
+        match fs::read_to_string(get_path_for_year(year)) {
+            Ok(s) => Answers::try_from(s).unwrap_or_else(|e| {
+                eprintln!("{e}");
+                Answers::default()
+            }),
+            Err(_) => Answers::default(),
+        }
+    }
+
+    /// Dehydrate answers of a year to a JSON file.
+    pub fn store_file(&self, year: Option<Year>) -> Result<(), Error> {
+        let json = JsonValue::from(self.clone());
+        let path = get_path_for_year(year);
+        if let Some(parent) = Path::new(&path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = fs::File::create(path)?;
+        json.format_to(&mut file)
+    }
+
+    /// Record the answer of a part, replacing the fingerprint of the day.
+    ///
+    /// The answer of the other part is kept if the fingerprint still matches, and dropped otherwise.
+    pub fn record(&mut self, day: Day, part: u8, answer: String, fingerprint: Fingerprint) {
+        let index = match self.data.iter().position(|a| a.day == day) {
+            Some(index) => index,
+            None => {
+                self.data.push(StoredAnswer {
+                    day,
+                    part_1: None,
+                    part_2: None,
+                    fingerprint: None,
+                });
+                self.data.sort_unstable_by_key(|a| a.day);
+                self.data.iter().position(|a| a.day == day).unwrap()
+            }
+        };
+
+        let entry = &mut self.data[index];
+        if !stale_changes(entry.fingerprint.as_ref(), &fingerprint).is_empty() {
+            entry.part_1 = None;
+            entry.part_2 = None;
+        }
+
+        match part {
+            1 => entry.part_1 = Some(answer),
+            _ => entry.part_2 = Some(answer),
+        }
+        entry.fingerprint = Some(fingerprint);
+    }
+
+    /// Returns which files changed since the answers of a day were recorded.
+    pub fn stale_changes(&self, day: Day, current: &Fingerprint) -> Vec<&'static str> {
+        self.data
+            .iter()
+            .find(|a| a.day == day)
+            .map(|a| stale_changes(a.fingerprint.as_ref(), current))
+            .unwrap_or_default()
+    }
+
+    /// Returns the known answer for a part of a day.
+    pub fn get(&self, day: Day, part: u8) -> Option<&str> {
+        let answer = self.data.iter().find(|a| a.day == day)?;
+        match part {
+            1 => answer.part_1.as_deref(),
+            2 => answer.part_2.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+fn get_path_for_year(year: Option<Year>) -> String {
+    config().scoped_path(&config().answers_path, year)
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<Answers> for JsonValue {
+    fn from(value: Answers) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for Answers {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_data = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("data")
+            .ok_or("expected JSON document to have key `data`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.data` to be an array.")?;
+
+        Ok(Answers {
+            data: json_data
+                .iter()
+                .map(StoredAnswer::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl From<&StoredAnswer> for JsonValue {
+    fn from(value: &StoredAnswer) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+
+        for (key, part) in [("part_1", &value.part_1), ("part_2", &value.part_2)] {
+            map.insert(
+                key.into(),
+                part.clone().map_or(JsonValue::Null, JsonValue::String),
+            );
+        }
+
+        if let Some(fingerprint) = &value.fingerprint {
+            fingerprint.insert_into(&mut map);
+        }
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for StoredAnswer {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected answer to be a JSON object.")?;
+
+        let day = json
+            .get("day")
+            .and_then(|v| v.get::<String>())
+            .and_then(|day| Day::from_str(day).ok())
+            .ok_or("Expected answer.day to be a Day struct.")?;
+
+        // answers are compared as strings, but plain numbers are accepted for convenience.
+        let part = |key: &str| match json.get(key) {
+            None | Some(JsonValue::Null) => Ok(None),
+            Some(JsonValue::String(s)) => Ok(Some(s.clone())),
+            Some(JsonValue::Number(n)) => Ok(Some(n.to_string())),
+            Some(_) => Err(format!(
+                "Expected answer.{key} to be null, string or number."
+            )),
+        };
+
+        Ok(StoredAnswer {
+            day,
+            part_1: part("part_1")?,
+            part_2: part("part_2")?,
+            fingerprint: Fingerprint::from_json(json)?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Answers;
+    use crate::{day, template::fingerprint::Fingerprint};
+
+    #[test]
+    fn handles_json_answers() {
+        let json = r#"{ "data": [{ "day": "01", "part_1": "abc", "part_2": 42 }, { "day": "03", "part_1": null }] }"#.to_string();
+        let answers = Answers::try_from(json).unwrap();
+        assert_eq!(answers.get(day!(1), 1), Some("abc"));
+        assert_eq!(answers.get(day!(1), 2), Some("42"));
+        assert_eq!(answers.get(day!(3), 1), None);
+        assert_eq!(answers.get(day!(3), 2), None);
+        assert_eq!(answers.get(day!(4), 1), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_malformed_answers() {
+        let json = r#"{ "data": [{ "day": "01", "part_1": [] }] }"#.to_string();
+        Answers::try_from(json).unwrap();
+    }
+
+    #[test]
+    fn records_answers() {
+        let fingerprint = |input: &str| Fingerprint {
+            input: Some(input.into()),
+            source: Some("src".into()),
+        };
+
+        let mut answers = Answers::default();
+        answers.record(day!(2), 1, "12".into(), fingerprint("a"));
+        answers.record(day!(2), 2, "34".into(), fingerprint("a"));
+        answers.record(day!(1), 1, "56".into(), fingerprint("a"));
+        assert_eq!(answers.data[0].day, day!(1));
+        assert_eq!(answers.get(day!(2), 1), Some("12"));
+        assert_eq!(answers.get(day!(2), 2), Some("34"));
+
+        // recording with a new input drops the stale answer of the other part.
+        assert_eq!(
+            answers.stale_changes(day!(2), &fingerprint("b")),
+            vec!["input"]
+        );
+        answers.record(day!(2), 1, "78".into(), fingerprint("b"));
+        assert_eq!(answers.get(day!(2), 1), Some("78"));
+        assert_eq!(answers.get(day!(2), 2), None);
+        assert!(answers.stale_changes(day!(2), &fingerprint("b")).is_empty());
+
+        let json = tinyjson::JsonValue::from(answers.clone())
+            .stringify()
+            .unwrap();
+        assert_eq!(Answers::try_from(json).unwrap().data, answers.data);
+    }
+}
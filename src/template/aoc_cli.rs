@@ -4,7 +4,8 @@ use std::{
     process::{Command, Output, Stdio},
 };
 
-use crate::template::Day;
+use crate::template::config::config;
+use crate::template::Puzzle;
 
 #[derive(Debug)]
 pub enum AocCommandError {
@@ -33,8 +34,8 @@ pub fn check() -> Result<(), AocCommandError> {
     Ok(())
 }
 
-pub fn read(day: Day) -> Result<Output, AocCommandError> {
-    let puzzle_path = get_puzzle_path(day);
+pub fn read(puzzle: Puzzle) -> Result<Output, AocCommandError> {
+    let puzzle_path = get_puzzle_path(puzzle);
 
     let args = build_args(
         "read",
@@ -43,15 +44,15 @@ pub fn read(day: Day) -> Result<Output, AocCommandError> {
             "--puzzle-file".into(),
             puzzle_path,
         ],
-        day,
+        puzzle,
     );
 
-    call_aoc_cli(&args)
+    call_aoc_cli(&args, Stdio::inherit())
 }
 
-pub fn download(day: Day) -> Result<Output, AocCommandError> {
-    let input_path = get_input_path(day);
-    let puzzle_path = get_puzzle_path(day);
+pub fn download(puzzle: Puzzle) -> Result<Output, AocCommandError> {
+    let input_path = get_input_path(puzzle);
+    let puzzle_path = get_puzzle_path(puzzle);
 
     let args = build_args(
         "download",
@@ -62,59 +63,67 @@ pub fn download(day: Day) -> Result<Output, AocCommandError> {
             "--puzzle-file".into(),
             puzzle_path.to_string(),
         ],
-        day,
+        puzzle,
     );
 
-    let output = call_aoc_cli(&args)?;
+    let output = call_aoc_cli(&args, Stdio::inherit())?;
     println!("---");
     println!("🎄 Successfully wrote input to \"{}\".", &input_path);
     println!("🎄 Successfully wrote puzzle to \"{}\".", &puzzle_path);
     Ok(output)
 }
 
-pub fn submit(day: Day, part: u8, result: &str) -> Result<Output, AocCommandError> {
+pub fn submit(puzzle: Puzzle, part: u8, result: &str) -> Result<Output, AocCommandError> {
     // workaround: the argument order is inverted for submit.
-    let mut args = build_args("submit", &[], day);
+    let mut args = build_args("submit", &[], puzzle);
     args.push(part.to_string());
     args.push(result.to_string());
-    call_aoc_cli(&args)
+
+    // the verdict is printed to stdout, capture it so the caller can tell whether the answer was right.
+    let result = call_aoc_cli(&args, Stdio::piped());
+    if let Ok(output) | Err(AocCommandError::BadExitStatus(output)) = &result {
+        print!("{}", String::from_utf8_lossy(&output.stdout));
+    }
+    result
 }
 
-fn get_input_path(day: Day) -> String {
-    format!("data/inputs/{day}.txt")
+/// Returns true if the output of `submit` reports a correct answer.
+pub fn is_correct_answer(output: &Output) -> bool {
+    String::from_utf8_lossy(&output.stdout).contains("That's the right answer")
 }
 
-fn get_puzzle_path(day: Day) -> String {
-    format!("data/puzzles/{day}.md")
+fn get_input_path(puzzle: Puzzle) -> String {
+    let day = puzzle.day();
+    format!("{}/{day}.txt", config().data_dir("inputs", puzzle.year()))
 }
 
-fn get_year() -> Option<u16> {
-    // match std::env::var("AOC_YEAR") {
-    //     Ok(x) => x.parse().ok().or(None),
-    //     Err(_) => None,
-    // }
-    // sjw
-    Some(2018)
+pub fn get_puzzle_path(puzzle: Puzzle) -> String {
+    let day = puzzle.day();
+    format!("{}/{day}.md", config().data_dir("puzzles", puzzle.year()))
 }
 
-fn build_args(command: &str, args: &[String], day: Day) -> Vec<String> {
+fn build_args(command: &str, args: &[String], puzzle: Puzzle) -> Vec<String> {
     let mut cmd_args = args.to_vec();
 
-    if let Some(year) = get_year() {
+    if let Some(year) = puzzle.year() {
         cmd_args.push("--year".into());
         cmd_args.push(year.to_string());
     }
 
-    cmd_args.append(&mut vec!["--day".into(), day.to_string(), command.into()]);
+    cmd_args.append(&mut vec![
+        "--day".into(),
+        puzzle.day().to_string(),
+        command.into(),
+    ]);
 
     cmd_args
 }
 
-fn call_aoc_cli(args: &[String]) -> Result<Output, AocCommandError> {
+fn call_aoc_cli(args: &[String], stdout: Stdio) -> Result<Output, AocCommandError> {
     // println!("Calling >aoc with: {}", args.join(" "));
     let output = Command::new("aoc")
         .args(args)
-        .stdout(Stdio::inherit())
+        .stdout(stdout)
         .stderr(Stdio::inherit())
         .output()
         .map_err(|_| AocCommandError::CommandNotCallable)?;
@@ -1,5 +1,5 @@
-use crate::template::{all_days, run_multi::run_multi};
+use crate::template::{all_days, report::Report, run_multi::run_multi, Year};
 
-pub fn handle(is_release: bool) {
-    run_multi(&all_days().collect(), is_release, false);
+pub fn handle(year: Option<Year>, is_release: bool, reports: &[Report]) {
+    run_multi(year, &all_days().collect(), is_release, false, reports);
 }
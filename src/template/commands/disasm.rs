@@ -0,0 +1,43 @@
+use std::{fs, path::Path, process};
+
+use crate::elfcode::{disasm::disassemble, encoding::parse_manual, parse_program, Instruction};
+use crate::parse::parse_all;
+use crate::template::{config::config, Puzzle};
+
+/// Print the ElfCode program in the input of a day as annotated pseudocode.
+pub fn handle(puzzle: Puzzle) {
+    let (ip_binding, program) = read_program(puzzle);
+    print!("{}", disassemble(ip_binding, &program));
+}
+
+/// Read the input of a day, exiting if that fails.
+pub fn read_input(puzzle: Puzzle) -> String {
+    let path = Path::new(&config().data_dir("inputs", puzzle.year()))
+        .join(format!("{}.txt", puzzle.day()));
+
+    fs::read_to_string(&path).unwrap_or_else(|_| {
+        eprintln!("could not read input file {}", path.display());
+        process::exit(1);
+    })
+}
+
+/// Read and parse the ElfCode program in the input of a day, exiting if that fails.
+///
+/// Manuals like the one of day 16 are decoded with the opcodes their samples give away.
+pub fn read_program(puzzle: Puzzle) -> (Option<usize>, Vec<Instruction>) {
+    let input = read_input(puzzle);
+
+    parse_all(parse_program, &input).unwrap_or_else(|e| {
+        let Ok(manual) = parse_all(parse_manual, &input) else {
+            eprintln!("input of {puzzle} is not an ElfCode program: {e}");
+            process::exit(1);
+        };
+        match manual.decode() {
+            Ok((_, program)) => (None, program),
+            Err(e) => {
+                eprintln!("could not decode the program in the input of {puzzle}: {e}");
+                process::exit(1);
+            }
+        }
+    })
+}
@@ -1,15 +1,139 @@
-use std::process;
+use std::{fs, path::Path, process};
 
-use crate::template::{aoc_cli, Day};
+use crate::template::{
+    aoc_cli, config::config, markdown, Day, Puzzle, Year, ANSI_BOLD, ANSI_RESET,
+};
+
+pub fn handle(puzzle: Puzzle) {
+    // puzzles that were downloaded before are rendered without calling aoc-cli.
+    if let Ok(content) = fs::read_to_string(aoc_cli::get_puzzle_path(puzzle)) {
+        println!("{}", markdown::render(&content, markdown::terminal_width()));
+        return;
+    }
 
-pub fn handle(day: Day) {
     if aoc_cli::check().is_err() {
         eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
         process::exit(1);
     }
 
-    if let Err(e) = aoc_cli::read(day) {
+    if let Err(e) = aoc_cli::read(puzzle) {
         eprintln!("failed to call aoc-cli: {e}");
         process::exit(1);
     };
 }
+
+/// Search all stored puzzle descriptions for a case-insensitive pattern.
+pub fn grep(pattern: &str) {
+    let mut matches = 0;
+
+    for puzzle in stored_puzzles() {
+        let Ok(content) = fs::read_to_string(aoc_cli::get_puzzle_path(puzzle)) else {
+            continue;
+        };
+
+        for (line_number, line) in search(&content, pattern) {
+            println!("{puzzle}:{line_number}: {}", highlight(line, pattern));
+            matches += 1;
+        }
+    }
+
+    if matches == 0 {
+        eprintln!("No stored puzzle matches \"{pattern}\".");
+        process::exit(1);
+    }
+}
+
+/// Returns all puzzles with a stored description, for every year of the repository.
+fn stored_puzzles() -> Vec<Puzzle> {
+    let mut years: Vec<Option<Year>> = crate::template::all_years().into_iter().map(Some).collect();
+    if years.is_empty() {
+        years.push(None);
+    }
+
+    let mut puzzles = vec![];
+
+    for year in years {
+        let Ok(entries) = fs::read_dir(config().data_dir("puzzles", year)) else {
+            continue;
+        };
+
+        let mut days: Vec<Day> = entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                let stem = Path::new(&path).file_stem()?.to_str()?.to_string();
+                (path.extension()? == "md").then_some(stem.parse().ok()?)
+            })
+            .collect();
+        days.sort_unstable();
+
+        puzzles.extend(days.into_iter().map(|day| Puzzle::new(year, day)));
+    }
+
+    puzzles
+}
+
+/// Returns the 1-based line numbers and lines of `content` that contain `pattern`, ignoring
+/// ASCII case like `highlight` does, so every line found has a match to highlight.
+fn search<'a>(content: &'a str, pattern: &str) -> Vec<(usize, &'a str)> {
+    let pattern = pattern.to_ascii_lowercase();
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.to_ascii_lowercase().contains(&pattern))
+        .map(|(i, line)| (i + 1, line.trim()))
+        .collect()
+}
+
+fn highlight(line: &str, pattern: &str) -> String {
+    // ascii lowercasing keeps byte offsets intact.
+    let haystack = line.to_ascii_lowercase();
+    let pattern = pattern.to_ascii_lowercase();
+    if pattern.is_empty() {
+        return line.to_string();
+    }
+
+    let mut result = String::new();
+    let mut last = 0;
+    for (start, _) in haystack.match_indices(&pattern) {
+        let end = start + pattern.len();
+        result.push_str(&line[last..start]);
+        result.push_str(&format!("{ANSI_BOLD}{}{ANSI_RESET}", &line[start..end]));
+        last = end;
+    }
+    result.push_str(&line[last..]);
+    result
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{highlight, search};
+    use crate::template::{ANSI_BOLD, ANSI_RESET};
+
+    #[test]
+    fn searches_case_insensitively() {
+        let content =
+            "The *instruction pointer* is bound\nto a register.\n  Instruction Pointer again";
+        assert_eq!(
+            search(content, "instruction pointer"),
+            vec![
+                (1, "The *instruction pointer* is bound"),
+                (3, "Instruction Pointer again")
+            ]
+        );
+        assert_eq!(search(content, "register"), vec![(2, "to a register.")]);
+
+        // only ASCII letters fold, like in `highlight`
+        assert_eq!(search("Élan", "élan"), vec![]);
+        assert_eq!(search("Élan", "ÉLAN"), vec![(1, "Élan")]);
+    }
+
+    #[test]
+    fn highlights_matches() {
+        assert_eq!(
+            highlight("Bound to a Register", "register"),
+            format!("Bound to a {ANSI_BOLD}Register{ANSI_RESET}")
+        );
+    }
+}
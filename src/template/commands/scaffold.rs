@@ -1,10 +1,10 @@
 use std::{
-    fs::{File, OpenOptions},
+    fs::{self, File, OpenOptions},
     io::Write,
     process,
 };
 
-use crate::template::Day;
+use crate::template::{config::config, Puzzle};
 
 const MODULE_TEMPLATE: &str =
     include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template.txt"));
@@ -21,10 +21,21 @@ fn create_file(path: &str) -> Result<File, std::io::Error> {
         .open(path)
 }
 
-pub fn handle(day: Day) {
-    let input_path = format!("data/inputs/{day}.txt");
-    let example_path = format!("data/examples/{day}.txt");
-    let module_path = format!("src/bin/{day}.rs");
+pub fn handle(puzzle: Puzzle) {
+    let day = puzzle.day();
+    let input_dir = config().data_dir("inputs", puzzle.year());
+    let example_dir = config().data_dir("examples", puzzle.year());
+    let input_path = format!("{input_dir}/{day}.txt");
+    let example_path = format!("{example_dir}/{day}.txt");
+    let module_path = format!("src/bin/{}.rs", puzzle.bin_name());
+
+    // per-year data directories might not exist yet.
+    for dir in [&input_dir, &example_dir] {
+        if let Err(e) = fs::create_dir_all(dir) {
+            eprintln!("Failed to create data directory: {e}");
+            process::exit(1);
+        }
+    }
 
     let mut file = match safe_create_file(&module_path) {
         Ok(file) => file,
@@ -69,5 +80,10 @@ pub fn handle(day: Day) {
     }
 
     println!("---");
-    println!("🎄 Type `cargo solve {day}` to run your solution.");
+    match puzzle.year() {
+        Some(year) if Some(year) != config().year => {
+            println!("🎄 Type `cargo solve {day} --year {year}` to run your solution.");
+        }
+        _ => println!("🎄 Type `cargo solve {day}` to run your solution."),
+    }
 }
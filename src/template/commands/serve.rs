@@ -0,0 +1,318 @@
+/// A small HTTP server that runs solutions in-process and answers with JSON.
+///
+/// Routes:
+///  - `POST /solve/{day}/{part}` solves the request body as puzzle input.
+///  - `GET /timings` returns the stored timings.
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    process,
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use tinyjson::JsonValue;
+
+use crate::template::{timings::Timings, Day, Puzzle, Year};
+use crate::Answer;
+
+/// Puzzle inputs are a few dozen kilobytes at most, so larger bodies are refused before they
+/// are read into memory.
+const MAX_BODY_LENGTH: usize = 4 * 1024 * 1024;
+
+/// A solution part that can be called in-process.
+pub struct Solution {
+    /// The name of the solution binary, i.e. `NN` or `YYYY-NN`.
+    pub bin: &'static str,
+    pub part: u8,
+    pub func: fn(&str) -> Option<Answer>,
+}
+
+struct Response {
+    status: u16,
+    body: JsonValue,
+}
+
+impl Response {
+    fn error(status: u16, message: &str) -> Self {
+        let body = HashMap::from([("error".to_string(), JsonValue::String(message.into()))]);
+        Self {
+            status,
+            body: JsonValue::Object(body),
+        }
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Content Too Large",
+        504 => "Gateway Timeout",
+        _ => "Internal Server Error",
+    }
+}
+
+pub fn handle(year: Option<Year>, port: u16, timeout: Duration, solutions: &'static [Solution]) {
+    let listener = match TcpListener::bind(("127.0.0.1", port)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Failed to bind to port {port}: {e}");
+            process::exit(1);
+        }
+    };
+
+    println!(
+        "🎄 Serving {} solutions on http://127.0.0.1:{port}",
+        solutions.len()
+    );
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                thread::spawn(move || {
+                    if let Err(e) = handle_connection(stream, year, timeout, solutions) {
+                        eprintln!("Failed to handle request: {e}");
+                    }
+                });
+            }
+            Err(e) => eprintln!("Failed to accept connection: {e}"),
+        }
+    }
+}
+
+fn handle_connection(
+    stream: TcpStream,
+    year: Option<Year>,
+    timeout: Duration,
+    solutions: &'static [Solution],
+) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut request = request_line.split_whitespace();
+    let method = request.next().unwrap_or_default().to_string();
+    let path = request.next().unwrap_or_default().to_string();
+
+    let response = match read_body(&mut reader)? {
+        Ok(body) => respond(&method, &path, body, year, timeout, solutions),
+        Err(response) => response,
+    };
+    println!("{method} {path} -> {}", response.status);
+    write_response(stream, &response)
+}
+
+/// Read the headers and the body of a request. Failing to read is an `io::Error`,
+/// a body that can't be accepted is answered with the `Response` that rejects it.
+fn read_body(reader: &mut impl BufRead) -> std::io::Result<Result<String, Response>> {
+    let mut content_length = Ok(0);
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse::<usize>();
+            }
+        }
+    }
+
+    let content_length = match content_length {
+        Ok(length) if length > MAX_BODY_LENGTH => {
+            let message = format!("expecting a body of at most {MAX_BODY_LENGTH} bytes");
+            return Ok(Err(Response::error(413, &message)));
+        }
+        Ok(length) => length,
+        Err(_) => {
+            let message = "expecting a Content-Length of a number of bytes";
+            return Ok(Err(Response::error(400, message)));
+        }
+    };
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    Ok(Ok(String::from_utf8_lossy(&body).into_owned()))
+}
+
+fn write_response(mut stream: TcpStream, response: &Response) -> std::io::Result<()> {
+    let body = response.body.stringify().unwrap_or_default();
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        response.status,
+        reason(response.status),
+        body.len()
+    )?;
+    stream.flush()
+}
+
+fn respond(
+    method: &str,
+    path: &str,
+    body: String,
+    year: Option<Year>,
+    timeout: Duration,
+    solutions: &'static [Solution],
+) -> Response {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    match (method, segments.as_slice()) {
+        ("GET", ["timings"]) => Response {
+            status: 200,
+            body: JsonValue::from(Timings::read_from_file(year)),
+        },
+        ("POST", ["solve", day, part]) => {
+            let Ok(day) = day.parse::<Day>() else {
+                return Response::error(400, "expecting a day number between 1 and 25");
+            };
+            let Ok(part) = part.parse::<u8>() else {
+                return Response::error(400, "expecting a part number of 1 or 2");
+            };
+
+            let bin = Puzzle::new(year, day).bin_name();
+            match solutions.iter().find(|s| s.bin == bin && s.part == part) {
+                Some(solution) => solve(solution, day, body, timeout),
+                None => Response::error(404, "no solution for this day and part"),
+            }
+        }
+        (_, ["timings"] | ["solve", _, _]) => Response::error(405, "method not allowed"),
+        _ => Response::error(404, "not found"),
+    }
+}
+
+/// Run a solution on a separate thread, giving up after `timeout`.
+///
+/// NOTE: threads cannot be cancelled, so a timed out solution keeps running in the background.
+fn solve(solution: &'static Solution, day: Day, input: String, timeout: Duration) -> Response {
+    let (sender, receiver) = mpsc::channel();
+    let func = solution.func;
+
+    thread::spawn(move || {
+        let timer = Instant::now();
+        let answer = func(&input);
+        let _ = sender.send((answer, timer.elapsed()));
+    });
+
+    match receiver.recv_timeout(timeout) {
+        Ok((answer, duration)) => {
+            let body = HashMap::from([
+                ("day".to_string(), JsonValue::String(day.to_string())),
+                (
+                    "part".to_string(),
+                    JsonValue::Number(f64::from(solution.part)),
+                ),
+                ("answer".to_string(), JsonValue::from(&Answer::from(answer))),
+                (
+                    "duration".to_string(),
+                    JsonValue::String(format!("{duration:.1?}")),
+                ),
+                (
+                    "duration_nanos".to_string(),
+                    JsonValue::Number(duration.as_nanos() as f64),
+                ),
+            ]);
+            Response {
+                status: 200,
+                body: JsonValue::Object(body),
+            }
+        }
+        Err(mpsc::RecvTimeoutError::Timeout) => {
+            Response::error(504, &format!("solution timed out after {timeout:?}"))
+        }
+        // the sender is dropped without sending if the solution panics.
+        Err(mpsc::RecvTimeoutError::Disconnected) => Response::error(500, "solution panicked"),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{collections::HashMap, time::Duration};
+
+    use tinyjson::JsonValue;
+
+    use super::{read_body, respond, Solution, MAX_BODY_LENGTH};
+
+    static SOLUTIONS: &[Solution] = &[
+        Solution {
+            bin: "01",
+            part: 1,
+            func: |input| Some(input.lines().count().into()),
+        },
+        Solution {
+            bin: "01",
+            part: 2,
+            func: |_| loop {
+                std::thread::sleep(Duration::from_millis(10));
+            },
+        },
+        Solution {
+            bin: "02",
+            part: 1,
+            func: |_| panic!("unsolvable"),
+        },
+    ];
+
+    fn call(method: &str, path: &str, body: &str) -> (u16, HashMap<String, JsonValue>) {
+        let response = respond(
+            method,
+            path,
+            body.into(),
+            None,
+            Duration::from_millis(50),
+            SOLUTIONS,
+        );
+        let body = response
+            .body
+            .get::<HashMap<String, JsonValue>>()
+            .cloned()
+            .unwrap_or_default();
+        (response.status, body)
+    }
+
+    #[test]
+    fn solves_inputs() {
+        let (status, body) = call("POST", "/solve/1/1", "a\nb\nc");
+        assert_eq!(status, 200);
+        assert_eq!(body["answer"]["value"], JsonValue::Number(3.0));
+        assert_eq!(body["answer"]["submit"], JsonValue::String("3".into()));
+        assert_eq!(body["day"], JsonValue::String("01".into()));
+        assert!(body.contains_key("duration_nanos"));
+    }
+
+    #[test]
+    fn handles_errors() {
+        assert_eq!(call("POST", "/solve/1/2", "").0, 504);
+        assert_eq!(call("POST", "/solve/2/1", "").0, 500);
+        assert_eq!(call("POST", "/solve/3/1", "").0, 404);
+        assert_eq!(call("POST", "/solve/26/1", "").0, 400);
+        assert_eq!(call("GET", "/solve/1/1", "").0, 405);
+        assert_eq!(call("GET", "/", "").0, 404);
+    }
+
+    #[test]
+    fn limits_bodies() {
+        let read = |request: &str| read_body(&mut request.as_bytes()).unwrap();
+
+        assert_eq!(
+            read("Host: localhost\r\nContent-Length: 3\r\n\r\nabcdef").ok(),
+            Some("abc".to_string())
+        );
+        assert_eq!(read("\r\n").ok(), Some(String::new()));
+
+        let too_large = format!("Content-Length: {}\r\n\r\n", MAX_BODY_LENGTH + 1);
+        assert_eq!(read(&too_large).unwrap_err().status, 413);
+        assert_eq!(read("content-length: -1\r\n\r\n").unwrap_err().status, 400);
+        assert_eq!(
+            read("Content-Length: lots\r\n\r\n").unwrap_err().status,
+            400
+        );
+    }
+}
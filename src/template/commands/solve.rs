@@ -1,9 +1,33 @@
+use std::io::{stdin, stdout};
 use std::process::{Command, Stdio};
 
-use crate::template::Day;
+use crate::elfcode::{debugger::Debugger, profiler::profile, Machine};
+use crate::template::{commands::disasm::read_program, Puzzle};
 
-pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
-    let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
+/// Run the ElfCode program in the input of a day instead of the solution.
+pub enum VmMode {
+    Debug,
+    /// Run at most `limit` instructions, starting with `r0` in register 0.
+    Profile {
+        limit: usize,
+        r0: usize,
+    },
+}
+
+pub fn handle(
+    puzzle: Puzzle,
+    release: bool,
+    dhat: bool,
+    submit_part: Option<u8>,
+    vm: Option<VmMode>,
+) {
+    match vm {
+        Some(VmMode::Debug) => return debug(puzzle),
+        Some(VmMode::Profile { limit, r0 }) => return profile_vm(puzzle, limit, r0),
+        None => {}
+    }
+
+    let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), puzzle.bin_name()];
 
     if dhat {
         cmd_args.extend([
@@ -32,3 +56,23 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
 
     cmd.wait().unwrap();
 }
+
+/// Step through the ElfCode program in the input of a day, instead of running the solution.
+fn debug(puzzle: Puzzle) {
+    let (ip_binding, program) = read_program(puzzle);
+    let mut debugger = Debugger::new(Machine::<6>::new(ip_binding, program));
+
+    println!("Debugging the input of {puzzle}. Type \"help\" for a list of commands.");
+    if let Err(e) = debugger.repl(stdin().lock(), &mut stdout()) {
+        eprintln!("debugger failed: {e}");
+    }
+}
+
+/// Print how often each instruction of the ElfCode program in the input of a day runs.
+fn profile_vm(puzzle: Puzzle, limit: usize, r0: usize) {
+    let (ip_binding, program) = read_program(puzzle);
+    let mut machine = Machine::<6>::new(ip_binding, program);
+    machine.registers[0] = r0;
+
+    print!("{}", profile(&mut machine, limit));
+}
@@ -1,34 +1,66 @@
 use std::collections::HashSet;
 
+use crate::template::fingerprint::Fingerprint;
+use crate::template::report::Report;
 use crate::template::run_multi::run_multi;
 use crate::template::timings::Timings;
-use crate::template::{all_days, readme_benchmarks, Day};
+use crate::template::{all_days, all_years, readme_benchmarks, Day, Puzzle, Year};
 
-pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
-    let stored_timings = Timings::read_from_file();
+pub fn handle(
+    year: Option<Year>,
+    day: Option<Day>,
+    run_all: bool,
+    store: bool,
+    reports: &[Report],
+) {
+    let stored_timings = Timings::read_from_file(year);
 
     let days_to_run = day.map_or_else(
         || {
             if run_all {
                 all_days().collect()
             } else {
-                // when the `--all` flag is not set, filter out days that are fully benched.
+                // when the `--all` flag is not set, filter out days that are fully benched,
+                // unless their input or source changed since.
                 all_days()
-                    .filter(|day| !stored_timings.is_day_complete(*day))
+                    .filter(|day| {
+                        if !stored_timings.is_day_complete(*day) {
+                            return true;
+                        }
+                        let fingerprint = Fingerprint::of(Puzzle::new(year, *day));
+                        let changes = stored_timings.stale_changes(*day, &fingerprint);
+                        if !changes.is_empty() {
+                            println!(
+                                "Timings of day {day} are stale ({} changed), re-running.",
+                                changes.join(" and ")
+                            );
+                        }
+                        !changes.is_empty()
+                    })
                     .collect()
             }
         },
         |day| HashSet::from([day]),
     );
 
-    let timings = run_multi(&days_to_run, true, true).unwrap();
+    let timings = run_multi(year, &days_to_run, true, true, reports).unwrap();
 
     if store {
         let merged_timings = stored_timings.merge(&timings);
-        merged_timings.store_file().unwrap();
+        merged_timings.store_file(year).unwrap();
+
+        // the readme lists every year that has solutions, not just the one that was benched.
+        let mut years: Vec<(Option<Year>, Timings)> = all_years()
+            .into_iter()
+            .filter(|y| Some(*y) != year)
+            .map(|y| (Some(y), Timings::read_from_file(Some(y))))
+            .filter(|(_, timings)| !timings.data.is_empty())
+            .collect();
+        years.push((year, merged_timings));
+        years.sort_unstable_by_key(|(year, _)| *year);
 
         println!();
-        match readme_benchmarks::update(merged_timings) {
+        match readme_benchmarks::update(&years) {
             Ok(()) => {
                 println!("Stored updated benchmarks.");
             }
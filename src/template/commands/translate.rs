@@ -0,0 +1,48 @@
+use std::process;
+
+use crate::elfcode::encoding::{parse_manual, parse_numeric_program, Encoding};
+use crate::parse::parse_all;
+use crate::template::{commands::disasm::read_input, Puzzle};
+
+/// Translate the ElfCode program in the input of a day between mnemonics and the numeric
+/// encoding of day 16, whichever way it is written.
+///
+/// Manuals are decoded with their own samples. Other programs use the encoding worked out
+/// from the samples in the input of `samples`.
+pub fn handle(puzzle: Puzzle, samples: Puzzle) {
+    let input = read_input(puzzle);
+
+    let translated = if let Ok(manual) = parse_all(parse_manual, &input) {
+        match manual.decode() {
+            Ok((_, program)) => Ok(program.iter().map(|i| format!("{i}\n")).collect()),
+            Err(e) => fail(&format!("could not decode the input of {puzzle}: {e}")),
+        }
+    } else if parse_all(parse_numeric_program, &input).is_ok() {
+        read_encoding(samples).decode(&input)
+    } else {
+        read_encoding(samples).encode(&input)
+    };
+
+    match translated {
+        Ok(program) => print!("{program}"),
+        Err(e) => fail(&format!("could not translate the input of {puzzle}: {e}")),
+    }
+}
+
+/// Work out the encoding from the samples in the input of a day, exiting if that fails.
+fn read_encoding(puzzle: Puzzle) -> Encoding {
+    let input = read_input(puzzle);
+
+    let manual = parse_all(parse_manual, &input)
+        .unwrap_or_else(|e| fail(&format!("input of {puzzle} has no opcode samples: {e}")));
+    Encoding::infer(&manual.samples).unwrap_or_else(|e| {
+        fail(&format!(
+            "could not work out the opcodes from {puzzle}: {e}"
+        ))
+    })
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{message}");
+    process::exit(1);
+}
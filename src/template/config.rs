@@ -0,0 +1,347 @@
+/// Project configuration, read from `aoc.toml` in the repository root.
+///
+/// The file supports a small subset of TOML: `[section]` headers and `key = value` pairs
+/// where values are strings, integers or booleans. Every key is optional and falls back
+/// to the template defaults.
+use std::{collections::HashMap, env, fs, path::Path, sync::OnceLock};
+
+use crate::template::{Year, ANSI_BOLD, ANSI_RESET};
+
+static CONFIG_FILE_PATH: &str = "aoc.toml";
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// Controls whether `solve --submit` is allowed to send answers to the server.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubmitPolicy {
+    /// Submit whenever `--submit` is passed.
+    Allow,
+    /// Ask for confirmation on the command-line before submitting.
+    Confirm,
+    /// Never submit, even if `--submit` is passed.
+    Never,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    pub year: Option<Year>,
+    pub inputs_dir: String,
+    pub examples_dir: String,
+    pub puzzles_dir: String,
+    pub timings_path: String,
+    pub answers_path: String,
+    pub readme_path: String,
+    pub release: bool,
+    pub time_all: bool,
+    pub time_store: bool,
+    pub submit_policy: SubmitPolicy,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            year: None,
+            inputs_dir: "data/inputs".into(),
+            examples_dir: "data/examples".into(),
+            puzzles_dir: "data/puzzles".into(),
+            timings_path: "data/timings.json".into(),
+            answers_path: "data/answers.json".into(),
+            readme_path: "README.md".into(),
+            release: false,
+            time_all: false,
+            time_store: false,
+            submit_policy: SubmitPolicy::Allow,
+        }
+    }
+}
+
+/// Returns the project configuration. The file is only read on first access.
+pub fn config() -> &'static Config {
+    CONFIG.get_or_init(Config::read_from_file)
+}
+
+impl Config {
+    /// Read the config file. If not present, returns the defaults.
+    pub fn read_from_file() -> Self {
+        let mut config = match fs::read_to_string(CONFIG_FILE_PATH) {
+            Ok(s) => Config::try_from(s).unwrap_or_else(|e| {
+                eprintln!("{ANSI_BOLD}{CONFIG_FILE_PATH}{ANSI_RESET}: {e}");
+                Config::default()
+            }),
+            Err(_) => Config::default(),
+        };
+
+        // the year can also be provided by the environment, e.g. via `.cargo/config.toml`.
+        if config.year.is_none() {
+            config.year = env::var("AOC_YEAR").ok().and_then(|x| x.parse().ok());
+        }
+
+        config
+    }
+
+    /// Returns the directory that holds files of a year for a data folder such as `inputs` or `examples`.
+    pub fn data_dir(&self, folder: &str, year: Option<Year>) -> String {
+        let path = match folder {
+            "inputs" => &self.inputs_dir,
+            "examples" => &self.examples_dir,
+            "puzzles" => &self.puzzles_dir,
+            folder => &format!("data/{folder}"),
+        };
+        self.scoped_path(path, year)
+    }
+
+    /// Scopes a configured path to a year.
+    ///
+    /// A `{year}` placeholder in the path is replaced with the year. Otherwise, the path is used
+    /// as-is for the configured year, and other years get a directory in front of the last
+    /// path component, e.g. `data/inputs` becomes `data/2017/inputs`.
+    pub fn scoped_path(&self, path: &str, year: Option<Year>) -> String {
+        if path.contains("{year}") {
+            return match year {
+                Some(year) => path.replace("{year}", &year.to_string()),
+                None => path.replace("{year}/", "").replace("/{year}", ""),
+            };
+        }
+
+        match year {
+            Some(year) if Some(year) != self.year => {
+                let path = Path::new(path);
+                let parent = path.parent().unwrap_or(Path::new(""));
+                let file_name = path.file_name().unwrap_or_default();
+                parent
+                    .join(year.to_string())
+                    .join(file_name)
+                    .to_string_lossy()
+                    .into_owned()
+            }
+            _ => path.into(),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Value {
+    String(String),
+    Integer(i64),
+    Boolean(bool),
+}
+
+fn parse_value(s: &str) -> Option<Value> {
+    if let Some(inner) = s.strip_prefix('"') {
+        let (value, rest) = inner.split_once('"')?;
+        let rest = rest.trim_start();
+        return (rest.is_empty() || rest.starts_with('#')).then(|| Value::String(value.into()));
+    }
+
+    // unquoted values end at the first comment.
+    let s = s.split('#').next()?.trim();
+    match s {
+        "true" => Some(Value::Boolean(true)),
+        "false" => Some(Value::Boolean(false)),
+        s => s.replace('_', "").parse().ok().map(Value::Integer),
+    }
+}
+
+/// Flattens the file to a map of `section.key` entries.
+fn parse_entries(s: &str) -> Result<HashMap<String, Value>, String> {
+    let mut entries = HashMap::new();
+    let mut section = String::new();
+
+    for (line, number) in s.lines().zip(1..) {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('[') {
+            section = header
+                .split(']')
+                .next()
+                .filter(|_| header.contains(']'))
+                .ok_or(format!("line {number}: unterminated section header."))?
+                .trim()
+                .to_string();
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or(format!("line {number}: expected `key = value`."))?;
+
+        let value = parse_value(value.trim()).ok_or(format!(
+            "line {number}: expected a string, integer or boolean."
+        ))?;
+
+        let key = if section.is_empty() {
+            key.trim().to_string()
+        } else {
+            format!("{section}.{}", key.trim())
+        };
+
+        entries.insert(key, value);
+    }
+
+    Ok(entries)
+}
+
+impl TryFrom<String> for Config {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let mut entries = parse_entries(&value)?;
+        let mut config = Config::default();
+
+        let mut string = |key: &str, target: &mut String| -> Result<(), String> {
+            match entries.remove(key) {
+                Some(Value::String(s)) => *target = s,
+                Some(_) => return Err(format!("expected `{key}` to be a string.")),
+                None => {}
+            };
+            Ok(())
+        };
+
+        string("paths.inputs", &mut config.inputs_dir)?;
+        string("paths.examples", &mut config.examples_dir)?;
+        string("paths.puzzles", &mut config.puzzles_dir)?;
+        string("paths.timings", &mut config.timings_path)?;
+        string("paths.answers", &mut config.answers_path)?;
+        string("paths.readme", &mut config.readme_path)?;
+
+        let mut boolean = |key: &str, target: &mut bool| -> Result<(), String> {
+            match entries.remove(key) {
+                Some(Value::Boolean(b)) => *target = b,
+                Some(_) => return Err(format!("expected `{key}` to be a boolean.")),
+                None => {}
+            };
+            Ok(())
+        };
+
+        boolean("run.release", &mut config.release)?;
+        boolean("time.all", &mut config.time_all)?;
+        boolean("time.store", &mut config.time_store)?;
+
+        config.year = match entries.remove("year") {
+            Some(Value::Integer(year)) => Some(
+                u16::try_from(year)
+                    .ok()
+                    .and_then(Year::new)
+                    .ok_or("expected `year` to be 2015 or later.")?,
+            ),
+            Some(_) => return Err("expected `year` to be an integer.".into()),
+            None => None,
+        };
+
+        config.submit_policy = match entries.remove("submit.policy") {
+            Some(Value::String(s)) => match s.as_str() {
+                "allow" => SubmitPolicy::Allow,
+                "confirm" => SubmitPolicy::Confirm,
+                "never" => SubmitPolicy::Never,
+                _ => return Err(
+                    "expected `submit.policy` to be one of \"allow\", \"confirm\" or \"never\"."
+                        .into(),
+                ),
+            },
+            Some(_) => return Err("expected `submit.policy` to be a string.".into()),
+            None => SubmitPolicy::Allow,
+        };
+
+        if let Some(key) = entries.keys().next() {
+            return Err(format!("unknown key `{key}`."));
+        }
+
+        Ok(config)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Config, SubmitPolicy};
+    use crate::template::Year;
+
+    #[test]
+    fn handles_empty_config() {
+        let config = Config::try_from(String::new()).unwrap();
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn handles_full_config() {
+        let toml = r#"
+# comment
+year = 2018
+
+[paths]
+inputs = "inputs" # trailing comment
+examples = "examples"
+puzzles = "puzzles"
+timings = "timings.json"
+answers = "answers.json"
+readme = "README.md"
+
+[run]
+release = true
+
+[time]
+all = false
+store = true
+
+[submit]
+policy = "confirm"
+"#;
+        let config = Config::try_from(toml.to_string()).unwrap();
+        assert_eq!(config.year, Year::new(2018));
+        assert_eq!(config.data_dir("inputs", None), "inputs");
+        assert_eq!(config.data_dir("examples", None), "examples");
+        assert_eq!(config.data_dir("puzzles", None), "puzzles");
+        assert_eq!(config.timings_path, "timings.json");
+        assert_eq!(config.answers_path, "answers.json");
+        assert!(config.release);
+        assert!(!config.time_all);
+        assert!(config.time_store);
+        assert_eq!(config.submit_policy, SubmitPolicy::Confirm);
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_unknown_keys() {
+        Config::try_from("[run]\nfast = true".to_string()).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_mistyped_values() {
+        Config::try_from("year = \"2018\"".to_string()).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_invalid_policies() {
+        Config::try_from("[submit]\npolicy = \"sometimes\"".to_string()).unwrap();
+    }
+
+    #[test]
+    fn scopes_paths_to_years() {
+        let config = Config::try_from("year = 2018".to_string()).unwrap();
+        let (primary, other) = (Year::new(2018), Year::new(2017));
+        assert_eq!(config.data_dir("inputs", primary), "data/inputs");
+        assert_eq!(config.data_dir("inputs", None), "data/inputs");
+        assert_eq!(config.data_dir("inputs", other), "data/2017/inputs");
+        assert_eq!(
+            config.scoped_path(&config.timings_path, other),
+            "data/2017/timings.json"
+        );
+        assert_eq!(
+            config.scoped_path("data/{year}/inputs", primary),
+            "data/2018/inputs"
+        );
+        assert_eq!(
+            config.scoped_path("data/{year}/inputs", None),
+            "data/inputs"
+        );
+    }
+}
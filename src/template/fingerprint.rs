@@ -0,0 +1,131 @@
+use std::{collections::HashMap, fs};
+use tinyjson::JsonValue;
+
+use crate::template::{config::config, run_multi::get_path_for_bin, Puzzle};
+
+/// Hashes of the files a result was computed from: the puzzle input and the solution source.
+/// Stored next to timings and answers, so results can be invalidated when either file changes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fingerprint {
+    pub input: Option<String>,
+    pub source: Option<String>,
+}
+
+impl Fingerprint {
+    /// Fingerprint the current input and solution source of a puzzle.
+    pub fn of(puzzle: Puzzle) -> Self {
+        let input_path = format!(
+            "{}/{}.txt",
+            config().data_dir("inputs", puzzle.year()),
+            puzzle.day()
+        );
+
+        Fingerprint {
+            input: hash_file(&input_path),
+            source: hash_file(&get_path_for_bin(puzzle)),
+        }
+    }
+
+    /// Returns which of the fingerprinted files differ between `self` and `current`.
+    pub fn changes(&self, current: &Fingerprint) -> Vec<&'static str> {
+        let mut changes = vec![];
+        if self.input != current.input {
+            changes.push("input");
+        }
+        if self.source != current.source {
+            changes.push("source");
+        }
+        changes
+    }
+
+    /// Add the fingerprint to the JSON object of a stored result.
+    pub fn insert_into(&self, map: &mut HashMap<String, JsonValue>) {
+        for (key, hash) in [("input_hash", &self.input), ("source_hash", &self.source)] {
+            map.insert(
+                key.into(),
+                hash.clone().map_or(JsonValue::Null, JsonValue::String),
+            );
+        }
+    }
+
+    /// Read the fingerprint of a stored result. Results stored without one return `None`.
+    pub fn from_json(map: &HashMap<String, JsonValue>) -> Result<Option<Self>, String> {
+        if !map.contains_key("input_hash") && !map.contains_key("source_hash") {
+            return Ok(None);
+        }
+
+        let hash = |key: &str| match map.get(key) {
+            None | Some(JsonValue::Null) => Ok(None),
+            Some(JsonValue::String(s)) => Ok(Some(s.clone())),
+            Some(_) => Err(format!("Expected {key} to be null or string.")),
+        };
+
+        Ok(Some(Fingerprint {
+            input: hash("input_hash")?,
+            source: hash("source_hash")?,
+        }))
+    }
+}
+
+/// Returns the changed files of a stored result, or nothing if it was stored without a fingerprint.
+pub fn stale_changes(stored: Option<&Fingerprint>, current: &Fingerprint) -> Vec<&'static str> {
+    stored.map(|f| f.changes(current)).unwrap_or_default()
+}
+
+fn hash_file(path: &str) -> Option<String> {
+    fs::read(path).ok().map(|bytes| hash(&bytes))
+}
+
+/// 64-bit FNV-1a. Unlike `DefaultHasher`, its output is stable across Rust versions.
+fn hash(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{hash:016x}")
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{hash, stale_changes, Fingerprint};
+
+    fn fingerprint(input: &str, source: &str) -> Fingerprint {
+        Fingerprint {
+            input: Some(hash(input.as_bytes())),
+            source: Some(hash(source.as_bytes())),
+        }
+    }
+
+    #[test]
+    fn hashes_are_stable() {
+        assert_eq!(hash(b""), "cbf29ce484222325");
+        assert_eq!(hash(b"a"), "af63dc4c8601ec8c");
+    }
+
+    #[test]
+    fn detects_changes() {
+        let stored = fingerprint("1 2 3", "fn main() {}");
+        assert!(stale_changes(Some(&stored), &fingerprint("1 2 3", "fn main() {}")).is_empty());
+        assert_eq!(
+            stale_changes(Some(&stored), &fingerprint("4 5 6", "fn main() {}")),
+            vec!["input"]
+        );
+        assert_eq!(
+            stale_changes(Some(&stored), &fingerprint("4 5 6", "")),
+            vec!["input", "source"]
+        );
+        assert!(stale_changes(None, &fingerprint("4 5 6", "")).is_empty());
+    }
+
+    #[test]
+    fn round_trips_json() {
+        let stored = fingerprint("1 2 3", "fn main() {}");
+        let mut map = HashMap::new();
+        stored.insert_into(&mut map);
+        assert_eq!(Fingerprint::from_json(&map).unwrap(), Some(stored));
+        assert_eq!(Fingerprint::from_json(&HashMap::new()).unwrap(), None);
+    }
+}
@@ -0,0 +1,373 @@
+/// Renders the markdown puzzle descriptions written by aoc-cli to the terminal.
+///
+/// Supports the subset of markdown those files use: ATX and setext headings, paragraphs,
+/// `*emphasis*`, `**strong**`, `` `code` ``, links, lists, block quotes and fenced or indented
+/// code blocks. Paragraphs and list items are wrapped to `width` columns.
+use crate::template::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+const ANSI_CODE: &str = "\x1b[36m";
+const ANSI_UNDERLINE: &str = "\x1b[4m";
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+struct Style {
+    strong: bool,
+    emphasis: bool,
+    code: bool,
+    link: bool,
+}
+
+impl Style {
+    fn escape(self) -> String {
+        let mut s = String::new();
+        if self.strong {
+            s.push_str(ANSI_BOLD);
+        }
+        if self.emphasis {
+            s.push_str(ANSI_ITALIC);
+        }
+        if self.code {
+            s.push_str(ANSI_CODE);
+        }
+        if self.link {
+            s.push_str(ANSI_UNDERLINE);
+        }
+        s
+    }
+}
+
+/// A word with inline escape codes, and the number of visible characters in it.
+struct Word {
+    text: String,
+    width: usize,
+}
+
+#[derive(Default)]
+struct WordBuilder {
+    words: Vec<Word>,
+    text: String,
+    width: usize,
+    /// The style that the escape codes in `text` currently apply.
+    applied: Style,
+}
+
+impl WordBuilder {
+    fn push(&mut self, c: char, style: Style) {
+        if style != self.applied {
+            if self.applied != Style::default() {
+                self.text.push_str(ANSI_RESET);
+            }
+            self.text.push_str(&style.escape());
+            self.applied = style;
+        }
+        self.text.push(c);
+        self.width += 1;
+    }
+
+    fn finish(&mut self) {
+        if self.width == 0 {
+            return;
+        }
+        if self.applied != Style::default() {
+            self.text.push_str(ANSI_RESET);
+        }
+        self.words.push(Word {
+            text: std::mem::take(&mut self.text),
+            width: self.width,
+        });
+        self.width = 0;
+        self.applied = Style::default();
+    }
+}
+
+/// Splits inline markdown into styled words.
+fn inline_words(text: &str) -> Vec<Word> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut builder = WordBuilder::default();
+    let mut style = Style::default();
+    // the positions of the `]` and `)` of the link being rendered.
+    let mut link_end: Option<(usize, usize)> = None;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if let Some((text_end, url_end)) = link_end {
+            if i == text_end {
+                // links render as their text, the url is dropped.
+                style.link = false;
+                link_end = None;
+                i = url_end + 1;
+                continue;
+            }
+        }
+
+        match c {
+            c if c.is_whitespace() => builder.finish(),
+            '\\' if !style.code && i + 1 < chars.len() => {
+                builder.push(chars[i + 1], style);
+                i += 1;
+            }
+            '`' => style.code = !style.code,
+            _ if style.code => builder.push(c, style),
+            '*' | '_' if chars.get(i + 1) == Some(&c) => {
+                style.strong = !style.strong;
+                i += 1;
+            }
+            // underscores inside words are literal, e.g. `snake_case`.
+            '_' if i > 0
+                && chars[i - 1].is_alphanumeric()
+                && chars.get(i + 1).is_some_and(|n| n.is_alphanumeric()) =>
+            {
+                builder.push(c, style)
+            }
+            '*' | '_' => style.emphasis = !style.emphasis,
+            '[' if link_end.is_none() => match find_link(&chars, i) {
+                Some(end) => {
+                    style.link = true;
+                    link_end = Some(end);
+                }
+                None => builder.push(c, style),
+            },
+            c => builder.push(c, style),
+        }
+
+        i += 1;
+    }
+
+    builder.finish();
+    builder.words
+}
+
+/// Returns the positions of the `]` and `)` of a `[text](url)` link starting at `start`.
+fn find_link(chars: &[char], start: usize) -> Option<(usize, usize)> {
+    let text_end = start + chars[start..].iter().position(|&c| c == ']')?;
+    if chars.get(text_end + 1) != Some(&'(') {
+        return None;
+    }
+    let url_end = text_end + chars[text_end..].iter().position(|&c| c == ')')?;
+    Some((text_end, url_end))
+}
+
+/// Wraps inline markdown to `width` columns, prefixing the first line with `first` and others with `rest`.
+fn wrap(text: &str, width: usize, first: &str, rest: &str) -> Vec<String> {
+    let mut lines = vec![];
+    let mut line = first.to_string();
+    let mut line_width = first.chars().count();
+    let mut is_empty = true;
+
+    for word in inline_words(text) {
+        if !is_empty && line_width + 1 + word.width > width {
+            lines.push(std::mem::replace(&mut line, rest.to_string()));
+            line_width = rest.chars().count();
+            is_empty = true;
+        }
+        if !is_empty {
+            line.push(' ');
+            line_width += 1;
+        }
+        line.push_str(&word.text);
+        line_width += word.width;
+        is_empty = false;
+    }
+
+    lines.push(line);
+    lines
+}
+
+fn list_marker(line: &str) -> Option<(&str, &str)> {
+    let trimmed = line.trim_start();
+    if let Some(rest) = trimmed
+        .strip_prefix("* ")
+        .or_else(|| trimmed.strip_prefix("- "))
+        .or_else(|| trimmed.strip_prefix("+ "))
+    {
+        return Some(("•", rest));
+    }
+    let (number, rest) = trimmed.split_once(". ")?;
+    number
+        .chars()
+        .all(|c| c.is_ascii_digit())
+        .then_some((number, rest))
+        .filter(|(n, _)| !n.is_empty())
+}
+
+fn is_setext_underline(line: &str) -> bool {
+    let line = line.trim();
+    line.len() >= 2 && (line.chars().all(|c| c == '-') || line.chars().all(|c| c == '='))
+}
+
+/// Render a markdown document for the terminal.
+pub fn render(markdown: &str, width: usize) -> String {
+    let lines: Vec<&str> = markdown.lines().collect();
+    let mut out: Vec<String> = vec![];
+    let mut paragraph: Vec<&str> = vec![];
+    let mut i = 0;
+
+    let flush = |paragraph: &mut Vec<&str>, out: &mut Vec<String>| {
+        if !paragraph.is_empty() {
+            out.extend(wrap(&paragraph.join(" "), width, "", ""));
+            out.push(String::new());
+            paragraph.clear();
+        }
+    };
+
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim();
+
+        // fenced code blocks are printed verbatim.
+        if trimmed.starts_with("```") {
+            flush(&mut paragraph, &mut out);
+            i += 1;
+            while i < lines.len() && !lines[i].trim().starts_with("```") {
+                out.push(format!("    {ANSI_CODE}{}{ANSI_RESET}", lines[i]));
+                i += 1;
+            }
+            out.push(String::new());
+            i += 1;
+            continue;
+        }
+
+        // indented code blocks start after a blank line.
+        if paragraph.is_empty() && line.starts_with("    ") && list_marker(line).is_none() {
+            while i < lines.len() && (lines[i].starts_with("    ") || lines[i].trim().is_empty()) {
+                out.push(format!(
+                    "    {ANSI_CODE}{}{ANSI_RESET}",
+                    &lines[i].get(4..).unwrap_or_default()
+                ));
+                i += 1;
+            }
+            while out
+                .last()
+                .is_some_and(|l| l.trim() == format!("{ANSI_CODE}{ANSI_RESET}"))
+            {
+                out.pop();
+            }
+            out.push(String::new());
+            continue;
+        }
+
+        if trimmed.is_empty() {
+            flush(&mut paragraph, &mut out);
+            i += 1;
+            continue;
+        }
+
+        if let Some(heading) = trimmed.strip_prefix('#') {
+            flush(&mut paragraph, &mut out);
+            let heading = heading.trim_start_matches('#').trim();
+            for line in wrap(heading, width, "", "") {
+                out.push(format!("{ANSI_BOLD}{line}{ANSI_RESET}"));
+            }
+            out.push(String::new());
+            i += 1;
+            continue;
+        }
+
+        if paragraph.is_empty() && lines.get(i + 1).is_some_and(|l| is_setext_underline(l)) {
+            for line in wrap(trimmed, width, "", "") {
+                out.push(format!("{ANSI_BOLD}{line}{ANSI_RESET}"));
+            }
+            out.push(String::new());
+            i += 2;
+            continue;
+        }
+
+        if let Some(quote) = trimmed.strip_prefix('>') {
+            flush(&mut paragraph, &mut out);
+            out.extend(wrap(quote.trim(), width, "│ ", "│ "));
+            i += 1;
+            continue;
+        }
+
+        if let Some((marker, item)) = list_marker(line) {
+            flush(&mut paragraph, &mut out);
+            let indent = line.len() - line.trim_start().len();
+            let first = format!("{}{marker} ", " ".repeat(indent));
+            let rest = " ".repeat(first.chars().count());
+            // continuation lines of the item are indented.
+            let mut item = item.to_string();
+            while lines.get(i + 1).is_some_and(|l| {
+                l.starts_with("  ") && !l.trim().is_empty() && list_marker(l).is_none()
+            }) {
+                i += 1;
+                item.push(' ');
+                item.push_str(lines[i].trim());
+            }
+            out.extend(wrap(&item, width, &first, &rest));
+            if lines.get(i + 1).is_some_and(|l| l.trim().is_empty()) {
+                out.push(String::new());
+            }
+            i += 1;
+            continue;
+        }
+
+        paragraph.push(trimmed);
+        i += 1;
+    }
+
+    flush(&mut paragraph, &mut out);
+
+    while out.last().is_some_and(|l| l.is_empty()) {
+        out.pop();
+    }
+
+    out.join("\n")
+}
+
+/// Returns the number of columns of the terminal, as reported by `$COLUMNS`.
+pub fn terminal_width() -> usize {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.parse::<usize>().ok())
+        .unwrap_or(80)
+        .clamp(40, 100)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{render, ANSI_CODE, ANSI_UNDERLINE};
+    use crate::template::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+    #[test]
+    fn renders_headings() {
+        let s = render("\\--- Day 1: Test ---\n----------\n\n## Part Two\n", 80);
+        assert_eq!(
+            s,
+            format!(
+                "{ANSI_BOLD}--- Day 1: Test ---{ANSI_RESET}\n\n{ANSI_BOLD}Part Two{ANSI_RESET}"
+            )
+        );
+    }
+
+    #[test]
+    fn renders_emphasis() {
+        let s = render("a *b c* **d** `e_f` [g](https://example.com)", 80);
+        assert_eq!(
+            s,
+            format!(
+                "a {ANSI_ITALIC}b{ANSI_RESET} {ANSI_ITALIC}c{ANSI_RESET} \
+                 {ANSI_BOLD}d{ANSI_RESET} {ANSI_CODE}e_f{ANSI_RESET} {ANSI_UNDERLINE}g{ANSI_RESET}"
+            )
+        );
+    }
+
+    #[test]
+    fn wraps_paragraphs() {
+        let s = render("one two three\nfour five", 10);
+        assert_eq!(s, "one two\nthree four\nfive");
+    }
+
+    #[test]
+    fn renders_lists_and_code() {
+        let s = render("* first item\n* second\n\n    #ip 0\n    seti 5 0 1\n", 80);
+        assert_eq!(
+            s,
+            format!(
+                "• first item\n• second\n\n    {ANSI_CODE}#ip 0{ANSI_RESET}\n    {ANSI_CODE}seti 5 0 1{ANSI_RESET}"
+            )
+        );
+    }
+}
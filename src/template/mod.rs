@@ -1,15 +1,26 @@
 use std::{env, fs};
 
+use config::config;
+
 pub mod aoc_cli;
 pub mod commands;
+pub mod config;
+pub mod report;
 pub mod runner;
 
 pub use day::*;
+pub use puzzle::*;
+pub use year::*;
 
+mod answers;
 mod day;
+mod fingerprint;
+mod markdown;
+mod puzzle;
 mod readme_benchmarks;
 mod run_multi;
 mod timings;
+mod year;
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
@@ -17,52 +28,73 @@ pub const ANSI_RESET: &str = "\x1b[0m";
 
 /// Helper function that reads a text file to a string.
 #[must_use]
-pub fn read_file(folder: &str, day: Day) -> String {
+pub fn read_file(folder: &str, puzzle: impl Into<Puzzle>) -> String {
+    let puzzle = puzzle.into();
+    let day = puzzle.day();
     let cwd = env::current_dir().unwrap();
-    let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
+    let filepath = cwd
+        .join(config().data_dir(folder, puzzle.year()))
+        .join(format!("{day}.txt"));
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
 }
 
 /// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
 #[must_use]
-pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
+pub fn read_file_part(folder: &str, puzzle: impl Into<Puzzle>, part: u8) -> String {
+    let puzzle = puzzle.into();
+    let day = puzzle.day();
     let cwd = env::current_dir().unwrap();
     let filepath = cwd
-        .join("data")
-        .join(folder)
+        .join(config().data_dir(folder, puzzle.year()))
         .join(format!("{day}-{part}.txt"));
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
 }
 
-/// Creates the constant `DAY` and sets up the input and runner for each part.
+/// Creates the constants `DAY` and `PUZZLE` and sets up the input and runner for each part.
 ///
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+///
+/// Days without a second part, like day 25, are declared with `single_part`. Only `part_one` is run,
+/// and the day counts as complete once part one is solved.
+///
+/// The year of `PUZZLE` is taken from the binary name (`src/bin/YYYY-NN.rs`), falling back to the configured year.
 #[macro_export]
 macro_rules! solution {
     ($day:expr) => {
-        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2]);
+        $crate::solution!(@impl $day, false, [part_one, 1] [part_two, 2]);
     };
     ($day:expr, 1) => {
-        $crate::solution!(@impl $day, [part_one, 1]);
+        $crate::solution!(@impl $day, false, [part_one, 1]);
     };
     ($day:expr, 2) => {
-        $crate::solution!(@impl $day, [part_two, 2]);
+        $crate::solution!(@impl $day, false, [part_two, 2]);
+    };
+    ($day:expr, single_part) => {
+        $crate::solution!(@impl $day, true, [part_one, 1]);
     };
 
-    (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
+    (@impl $day:expr, $single_part:expr, $( [$func:expr, $part:expr] )*) => {
         /// The current day.
         const DAY: $crate::template::Day = $crate::day!($day);
 
-        #[cfg(feature = "dhat-heap")]
+        /// The current puzzle, i.e. the current day in the year of this solution.
+        const PUZZLE: $crate::template::Puzzle =
+            $crate::template::Puzzle::__from_bin_name(env!("CARGO_BIN_NAME"), DAY);
+
+        // with `serve`, solutions are also compiled as modules of the main binary, which can only have one allocator.
+        #[cfg(all(feature = "dhat-heap", not(feature = "serve")))]
         #[global_allocator]
         static ALLOC: dhat::Alloc = dhat::Alloc;
 
         fn main() {
             use $crate::template::runner::*;
-            let input = $crate::template::read_file("inputs", DAY);
-            $( run_part($func, &input, DAY, $part); )*
+            let input = $crate::template::read_file("inputs", PUZZLE);
+            $( run_part($func, &input, PUZZLE, $part); )*
+            if $single_part {
+                print_no_second_part();
+            }
         }
     };
 }
@@ -0,0 +1,125 @@
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+
+use crate::template::config::config;
+use crate::template::{Day, Year};
+
+/// Identifies a puzzle by its year and day.
+///
+/// Puzzles without an explicit year belong to the year configured in `aoc.toml`.
+/// Those keep the flat layout (`src/bin/NN.rs`, `data/inputs/NN.txt`), while puzzles of
+/// other years live in `src/bin/YYYY-NN.rs` and `data/YYYY/inputs/NN.txt`.
+///
+/// # Display
+/// This value displays as `YYYY-NN` if the year is known, and as `NN` otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Puzzle {
+    year: Option<Year>,
+    day: Day,
+}
+
+impl Puzzle {
+    /// Creates a [`Puzzle`]. If `year` is [`None`], the configured year is used.
+    pub const fn new(year: Option<Year>, day: Day) -> Self {
+        Self { year, day }
+    }
+
+    // Not part of the public API
+    #[doc(hidden)]
+    pub const fn __from_bin_name(name: &str, day: Day) -> Self {
+        Self {
+            year: year_from_bin_name(name),
+            day,
+        }
+    }
+
+    /// Returns the year of the puzzle, falling back to the configured year.
+    pub fn year(&self) -> Option<Year> {
+        self.year.or(config().year)
+    }
+
+    pub fn day(&self) -> Day {
+        self.day
+    }
+
+    /// Returns the name of the solution binary, i.e. `YYYY-NN` or `NN`.
+    pub fn bin_name(&self) -> String {
+        match self.year() {
+            Some(year)
+                if Some(year) != config().year
+                    || Path::new(&format!("src/bin/{year}-{}.rs", self.day)).exists() =>
+            {
+                format!("{year}-{}", self.day)
+            }
+            _ => self.day.to_string(),
+        }
+    }
+}
+
+impl From<Day> for Puzzle {
+    fn from(day: Day) -> Self {
+        Self::new(None, day)
+    }
+}
+
+impl Display for Puzzle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.year() {
+            Some(year) => write!(f, "{year}-{}", self.day),
+            None => write!(f, "{}", self.day),
+        }
+    }
+}
+
+/// Parses the year from a binary name such as `2018-01` in a const context.
+const fn year_from_bin_name(name: &str) -> Option<Year> {
+    let bytes = name.as_bytes();
+    if bytes.len() != 7 || bytes[4] != b'-' {
+        return None;
+    }
+
+    let mut year: u16 = 0;
+    let mut i = 0;
+    while i < 4 {
+        if !bytes[i].is_ascii_digit() {
+            return None;
+        }
+        year = year * 10 + (bytes[i] - b'0') as u16;
+        i += 1;
+    }
+
+    Year::new(year)
+}
+
+/// Returns every year with solutions in this repository, sorted: the configured year
+/// and all years that have `src/bin/YYYY-NN.rs` binaries.
+pub fn all_years() -> Vec<Year> {
+    let mut years: BTreeSet<Year> = config().year.into_iter().collect();
+
+    if let Ok(entries) = fs::read_dir("src/bin") {
+        years.extend(entries.filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            year_from_bin_name(name.strip_suffix(".rs")?)
+        }));
+    }
+
+    years.into_iter().collect()
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{year_from_bin_name, Year};
+
+    #[test]
+    fn parses_years_from_bin_names() {
+        assert_eq!(year_from_bin_name("2017-01"), Year::new(2017));
+        assert_eq!(year_from_bin_name("2017-25"), Year::new(2017));
+        assert_eq!(year_from_bin_name("01"), None);
+        assert_eq!(year_from_bin_name("1999-01"), None);
+        assert_eq!(year_from_bin_name("20x7-01"), None);
+    }
+}
@@ -2,8 +2,9 @@
 /// The approach taken is similar to how `aoc-readme-stars` handles this.
 use std::{fs, io};
 
+use crate::template::config::config;
 use crate::template::timings::Timings;
-use crate::template::Day;
+use crate::template::{Puzzle, Year};
 
 static MARKER: &str = "<!--- benchmarking table --->";
 
@@ -26,8 +27,8 @@ pub struct TablePosition {
 }
 
 #[must_use]
-pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
+pub fn get_path_for_bin(puzzle: Puzzle) -> String {
+    format!("./src/bin/{}.rs", puzzle.bin_name())
 }
 
 fn locate_table(readme: &str) -> Result<TablePosition, Error> {
@@ -52,27 +53,60 @@ fn locate_table(readme: &str) -> Result<TablePosition, Error> {
     Ok(TablePosition { pos_start, pos_end })
 }
 
-fn construct_table(prefix: &str, timings: Timings, total_millis: f64) -> String {
-    let header = format!("{prefix} Benchmarks");
-
-    let mut lines: Vec<String> = vec![
-        MARKER.into(),
-        header,
-        String::new(),
-        "| Day | Part 1 | Part 2 |".into(),
-        "| :---: | :---: | :---:  |".into(),
-    ];
+fn construct_day_table(lines: &mut Vec<String>, year: Option<Year>, timings: &Timings) {
+    lines.push("| Day | Part 1 | Part 2 |".into());
+    lines.push("| :---: | :---: | :---:  |".into());
 
-    for timing in timings.data {
-        let path = get_path_for_bin(timing.day);
+    for timing in &timings.data {
+        let path = get_path_for_bin(Puzzle::new(year, timing.day));
+        let part_2 = if timing.single_part {
+            "n/a".into()
+        } else {
+            format!("`{}`", timing.part_2.as_deref().unwrap_or("-"))
+        };
         lines.push(format!(
-            "| [Day {}]({}) | `{}` | `{}` |",
+            "| [Day {}]({}) | `{}` | {part_2} |",
             timing.day.into_inner(),
             path,
-            timing.part_1.unwrap_or_else(|| "-".into()),
-            timing.part_2.unwrap_or_else(|| "-".into())
+            timing.part_1.as_deref().unwrap_or("-"),
         ));
     }
+}
+
+/// Constructs the benchmark table. With more than one year, a summary table
+/// is followed by a table for each year.
+fn construct_table(prefix: &str, years: &[(Option<Year>, Timings)], total_millis: f64) -> String {
+    let header = format!("{prefix} Benchmarks");
+
+    let mut lines: Vec<String> = vec![MARKER.into(), header, String::new()];
+
+    match years {
+        [(year, timings)] => construct_day_table(&mut lines, *year, timings),
+        _ => {
+            lines.push("| Year | Days | Total |".into());
+            lines.push("| :---: | :---: | :---: |".into());
+
+            for (year, timings) in years {
+                lines.push(format!(
+                    "| [{}](#{}) | {} | `{:.2}ms` |",
+                    year.map_or("-".into(), |y| y.to_string()),
+                    year.map_or("-".into(), |y| y.to_string()),
+                    timings.data.len(),
+                    timings.total_millis()
+                ));
+            }
+
+            for (year, timings) in years {
+                lines.push(String::new());
+                lines.push(format!(
+                    "{prefix}# {}",
+                    year.map_or("-".into(), |y| y.to_string())
+                ));
+                lines.push(String::new());
+                construct_day_table(&mut lines, *year, timings);
+            }
+        }
+    }
 
     lines.push(String::new());
     lines.push(format!("**Total: {total_millis:.2}ms**"));
@@ -81,18 +115,23 @@ fn construct_table(prefix: &str, timings: Timings, total_millis: f64) -> String
     lines.join("\n")
 }
 
-fn update_content(s: &mut String, timings: Timings, total_millis: f64) -> Result<(), Error> {
+fn update_content(
+    s: &mut String,
+    years: &[(Option<Year>, Timings)],
+    total_millis: f64,
+) -> Result<(), Error> {
     let positions = locate_table(s)?;
-    let table = construct_table("##", timings, total_millis);
+    let table = construct_table("##", years, total_millis);
     s.replace_range(positions.pos_start..positions.pos_end, &table);
     Ok(())
 }
 
-pub fn update(timings: Timings) -> Result<(), Error> {
-    let path = "README.md";
+/// Update the readme with timings for one or more years.
+pub fn update(years: &[(Option<Year>, Timings)]) -> Result<(), Error> {
+    let path = &config().readme_path;
     let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
-    let total_millis = timings.total_millis();
-    update_content(&mut readme, timings, total_millis)?;
+    let total_millis = years.iter().map(|(_, t)| t.total_millis()).sum();
+    update_content(&mut readme, years, total_millis)?;
     fs::write(path, &readme)?;
     Ok(())
 }
@@ -100,7 +139,7 @@ pub fn update(timings: Timings) -> Result<(), Error> {
 #[cfg(feature = "test_lib")]
 mod tests {
     use super::{update_content, MARKER};
-    use crate::{day, template::timings::Timing, template::timings::Timings};
+    use crate::{day, template::timings::Timing, template::timings::Timings, template::Year};
 
     fn get_mock_timings() -> Timings {
         Timings {
@@ -110,18 +149,24 @@ mod tests {
                     part_1: Some("10ms".into()),
                     part_2: Some("20ms".into()),
                     total_nanos: 3e+10,
+                    fingerprint: None,
+                    single_part: false,
                 },
                 Timing {
                     day: day!(2),
                     part_1: Some("30ms".into()),
                     part_2: Some("40ms".into()),
                     total_nanos: 7e+10,
+                    fingerprint: None,
+                    single_part: false,
                 },
                 Timing {
                     day: day!(4),
                     part_1: Some("40ms".into()),
                     part_2: Some("50ms".into()),
                     total_nanos: 9e+10,
+                    fingerprint: None,
+                    single_part: false,
                 },
             ],
         }
@@ -131,28 +176,28 @@ mod tests {
     #[should_panic]
     fn errors_if_marker_not_present() {
         let mut s = "# readme".to_string();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, &[(None, get_mock_timings())], 190.0).unwrap();
     }
 
     #[test]
     #[should_panic]
     fn errors_if_too_many_markers_present() {
         let mut s = format!("{} {} {}", MARKER, MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, &[(None, get_mock_timings())], 190.0).unwrap();
     }
 
     #[test]
     fn updates_empty_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, &[(None, get_mock_timings())], 190.0).unwrap();
         assert_eq!(s.contains("## Benchmarks"), true);
     }
 
     #[test]
     fn updates_existing_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, &[(None, get_mock_timings())], 190.0).unwrap();
+        update_content(&mut s, &[(None, get_mock_timings())], 190.0).unwrap();
         assert_eq!(s.matches(MARKER).collect::<Vec<&str>>().len(), 2);
         assert_eq!(s.matches("## Benchmarks").collect::<Vec<&str>>().len(), 1);
     }
@@ -160,7 +205,7 @@ mod tests {
     #[test]
     fn format_benchmarks() {
         let mut s = format!("foo\nbar\n{}\n{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, &[(None, get_mock_timings())], 190.0).unwrap();
         let expected = [
             "foo",
             "bar",
@@ -180,4 +225,73 @@ mod tests {
         .join("\n");
         assert_eq!(s, expected);
     }
+
+    #[test]
+    fn format_single_part_days() {
+        let mut s = format!("{}\n{}", MARKER, MARKER);
+        let timings = Timings {
+            data: vec![Timing {
+                day: day!(25),
+                part_1: Some("1ms".into()),
+                part_2: None,
+                total_nanos: 1e+6,
+                fingerprint: None,
+                single_part: true,
+            }],
+        };
+        update_content(&mut s, &[(None, timings)], 1.0).unwrap();
+        assert!(s.contains("| [Day 25](./src/bin/25.rs) | `1ms` | n/a |"));
+    }
+
+    #[test]
+    fn format_multi_year_benchmarks() {
+        let mut s = format!("{}\n{}", MARKER, MARKER);
+        let other = Timings {
+            data: vec![Timing {
+                day: day!(3),
+                part_1: Some("1ms".into()),
+                part_2: None,
+                total_nanos: 1e+6,
+                fingerprint: None,
+                single_part: false,
+            }],
+        };
+        update_content(
+            &mut s,
+            &[
+                (Year::new(2017), other),
+                (Year::new(2018), get_mock_timings()),
+            ],
+            190.0,
+        )
+        .unwrap();
+        let expected = [
+            "<!--- benchmarking table --->",
+            "## Benchmarks",
+            "",
+            "| Year | Days | Total |",
+            "| :---: | :---: | :---: |",
+            "| [2017](#2017) | 1 | `1.00ms` |",
+            "| [2018](#2018) | 3 | `190000.00ms` |",
+            "",
+            "### 2017",
+            "",
+            "| Day | Part 1 | Part 2 |",
+            "| :---: | :---: | :---:  |",
+            "| [Day 3](./src/bin/2017-03.rs) | `1ms` | `-` |",
+            "",
+            "### 2018",
+            "",
+            "| Day | Part 1 | Part 2 |",
+            "| :---: | :---: | :---:  |",
+            "| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` |",
+            "| [Day 2](./src/bin/02.rs) | `30ms` | `40ms` |",
+            "| [Day 4](./src/bin/04.rs) | `40ms` | `50ms` |",
+            "",
+            "**Total: 190.00ms**",
+            "<!--- benchmarking table --->",
+        ]
+        .join("\n");
+        assert_eq!(s, expected);
+    }
 }
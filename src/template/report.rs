@@ -0,0 +1,315 @@
+/// Module that writes the results of a multi-day run as JUnit XML or TAP reports,
+/// checking every answer against the known answers.
+use std::{error::Error, fmt::Display, fs, io, str::FromStr};
+
+use crate::template::answers::Answers;
+use crate::template::Puzzle;
+
+/// The outcome of running a single part of a solution.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartResult {
+    pub puzzle: Puzzle,
+    pub part: u8,
+    pub answer: Option<String>,
+    pub nanos: Option<f64>,
+}
+
+/// A report requested with `--report`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Report {
+    /// `--report junit=<path>`
+    Junit(String),
+    /// `--report tap` writes to stdout, `--report tap=<path>` to a file.
+    Tap(Option<String>),
+}
+
+impl FromStr for Report {
+    type Err = ReportFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            Some(("junit", path)) if !path.is_empty() => Ok(Report::Junit(path.into())),
+            Some(("tap", path)) if !path.is_empty() => Ok(Report::Tap(Some(path.into()))),
+            None if s == "tap" => Ok(Report::Tap(None)),
+            _ => Err(ReportFromStrError),
+        }
+    }
+}
+
+/// An error which can be returned when parsing a [`Report`].
+#[derive(Debug)]
+pub struct ReportFromStrError;
+
+impl Error for ReportFromStrError {}
+
+impl Display for ReportFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expecting a report of `junit=<path>`, `tap` or `tap=<path>`")
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Debug, PartialEq, Eq)]
+enum Verdict<'a> {
+    /// The answer matches the known answer, or there is no known answer to compare with.
+    Pass {
+        verified: bool,
+    },
+    Fail {
+        expected: &'a str,
+    },
+    /// The solution did not return an answer.
+    Skip,
+}
+
+fn verdict<'a>(result: &PartResult, answers: &'a Answers) -> Verdict<'a> {
+    let Some(answer) = &result.answer else {
+        return Verdict::Skip;
+    };
+
+    match answers.get(result.puzzle.day(), result.part) {
+        Some(expected) if expected == answer => Verdict::Pass { verified: true },
+        Some(expected) => Verdict::Fail { expected },
+        None => Verdict::Pass { verified: false },
+    }
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn seconds(nanos: Option<f64>) -> String {
+    format!("{:.6}", nanos.unwrap_or(0.0) / 1_000_000_000_f64)
+}
+
+fn junit(results: &[PartResult], answers: &Answers) -> String {
+    let verdicts: Vec<_> = results.iter().map(|r| verdict(r, answers)).collect();
+    let failures = verdicts
+        .iter()
+        .filter(|v| matches!(v, Verdict::Fail { .. }))
+        .count();
+    let skipped = verdicts.iter().filter(|v| **v == Verdict::Skip).count();
+    let time = seconds(Some(results.iter().filter_map(|r| r.nanos).sum()));
+    let name = results
+        .first()
+        .and_then(|r| r.puzzle.year())
+        .map_or("advent_of_code".into(), |y| y.to_string());
+
+    let mut lines = vec![
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_string(),
+        format!(
+            r#"<testsuites name="advent_of_code" tests="{}" failures="{failures}" skipped="{skipped}" time="{time}">"#,
+            results.len()
+        ),
+        format!(
+            r#"  <testsuite name="{name}" tests="{}" failures="{failures}" skipped="{skipped}" time="{time}">"#,
+            results.len()
+        ),
+    ];
+
+    for (result, verdict) in results.iter().zip(&verdicts) {
+        lines.push(format!(
+            r#"    <testcase classname="{}" name="part_{}" time="{}">"#,
+            result.puzzle,
+            result.part,
+            seconds(result.nanos)
+        ));
+
+        match verdict {
+            Verdict::Skip => lines.push(r#"      <skipped message="not solved"/>"#.into()),
+            Verdict::Fail { expected } => lines.push(format!(
+                r#"      <failure message="expected {}, got {}"/>"#,
+                escape_xml(expected),
+                escape_xml(result.answer.as_deref().unwrap_or_default())
+            )),
+            Verdict::Pass { .. } => {}
+        }
+
+        if let Some(answer) = &result.answer {
+            lines.push(format!(
+                "      <system-out>{}</system-out>",
+                escape_xml(answer)
+            ));
+        }
+
+        lines.push("    </testcase>".into());
+    }
+
+    lines.push("  </testsuite>".into());
+    lines.push("</testsuites>".into());
+    lines.join("\n") + "\n"
+}
+
+fn tap(results: &[PartResult], answers: &Answers) -> String {
+    let mut lines = vec![
+        "TAP version 13".to_string(),
+        format!("1..{}", results.len()),
+    ];
+
+    for (result, i) in results.iter().zip(1..) {
+        let description = format!("{} part {}", result.puzzle, result.part);
+        // multi-line answers are reported in the YAML block only.
+        let answer = result.answer.as_deref().unwrap_or_default();
+        let inline_answer = if answer.contains('\n') { "▼" } else { answer };
+
+        match verdict(result, answers) {
+            Verdict::Skip => lines.push(format!("ok {i} - {description} # SKIP not solved")),
+            Verdict::Pass { verified } => {
+                let note = if verified { "" } else { ", unverified" };
+                lines.push(format!("ok {i} - {description} # {inline_answer}{note}"));
+            }
+            Verdict::Fail { expected } => {
+                lines.push(format!("not ok {i} - {description}"));
+                lines.push("  ---".into());
+                lines.push(format!("  expected: {expected:?}"));
+                lines.push(format!("  got: {answer:?}"));
+                lines.push("  ...".into());
+                continue;
+            }
+        }
+
+        if let Some(nanos) = result.nanos {
+            lines.push("  ---".into());
+            lines.push(format!("  duration_ms: {:.6}", nanos / 1_000_000_f64));
+            if answer.contains('\n') {
+                lines.push(format!("  answer: {answer:?}"));
+            }
+            lines.push("  ...".into());
+        }
+    }
+
+    lines.join("\n") + "\n"
+}
+
+/// Write all requested reports for the results of a run.
+pub fn write(reports: &[Report], results: &[PartResult], answers: &Answers) -> io::Result<()> {
+    for report in reports {
+        match report {
+            Report::Junit(path) => {
+                fs::write(path, junit(results, answers))?;
+                println!("Wrote JUnit report to \"{path}\".");
+            }
+            Report::Tap(Some(path)) => {
+                fs::write(path, tap(results, answers))?;
+                println!("Wrote TAP report to \"{path}\".");
+            }
+            Report::Tap(None) => {
+                println!();
+                print!("{}", tap(results, answers));
+            }
+        }
+    }
+    Ok(())
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{junit, tap, PartResult, Report};
+    use crate::{
+        day,
+        template::answers::{Answers, StoredAnswer},
+        template::{Puzzle, Year},
+    };
+
+    fn get_mock_results() -> (Vec<PartResult>, Answers) {
+        let puzzle = Puzzle::new(Year::new(2018), day!(1));
+        let results = vec![
+            PartResult {
+                puzzle,
+                part: 1,
+                answer: Some("42".into()),
+                nanos: Some(1_500_000.0),
+            },
+            PartResult {
+                puzzle,
+                part: 2,
+                answer: Some("<7>".into()),
+                nanos: Some(500_000.0),
+            },
+            PartResult {
+                puzzle: Puzzle::new(Year::new(2018), day!(2)),
+                part: 1,
+                answer: None,
+                nanos: None,
+            },
+        ];
+        let answers = Answers {
+            data: vec![StoredAnswer {
+                day: day!(1),
+                part_1: Some("42".into()),
+                part_2: Some("8".into()),
+                fingerprint: None,
+            }],
+        };
+        (results, answers)
+    }
+
+    #[test]
+    fn parses_reports() {
+        assert_eq!(
+            "junit=out.xml".parse::<Report>().unwrap(),
+            Report::Junit("out.xml".into())
+        );
+        assert_eq!("tap".parse::<Report>().unwrap(), Report::Tap(None));
+        assert_eq!(
+            "tap=out.tap".parse::<Report>().unwrap(),
+            Report::Tap(Some("out.tap".into()))
+        );
+        assert!("junit".parse::<Report>().is_err());
+        assert!("html=out.html".parse::<Report>().is_err());
+    }
+
+    #[test]
+    fn formats_junit() {
+        let (results, answers) = get_mock_results();
+        let expected = [
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+            r#"<testsuites name="advent_of_code" tests="3" failures="1" skipped="1" time="0.002000">"#,
+            r#"  <testsuite name="2018" tests="3" failures="1" skipped="1" time="0.002000">"#,
+            r#"    <testcase classname="2018-01" name="part_1" time="0.001500">"#,
+            r#"      <system-out>42</system-out>"#,
+            r#"    </testcase>"#,
+            r#"    <testcase classname="2018-01" name="part_2" time="0.000500">"#,
+            r#"      <failure message="expected 8, got &lt;7&gt;"/>"#,
+            r#"      <system-out>&lt;7&gt;</system-out>"#,
+            r#"    </testcase>"#,
+            r#"    <testcase classname="2018-02" name="part_1" time="0.000000">"#,
+            r#"      <skipped message="not solved"/>"#,
+            r#"    </testcase>"#,
+            r#"  </testsuite>"#,
+            r#"</testsuites>"#,
+            "",
+        ]
+        .join("\n");
+        assert_eq!(junit(&results, &answers), expected);
+    }
+
+    #[test]
+    fn formats_tap() {
+        let (results, answers) = get_mock_results();
+        let expected = [
+            "TAP version 13",
+            "1..3",
+            "ok 1 - 2018-01 part 1 # 42",
+            "  ---",
+            "  duration_ms: 1.500000",
+            "  ...",
+            "not ok 2 - 2018-01 part 2",
+            "  ---",
+            "  expected: \"8\"",
+            "  got: \"<7>\"",
+            "  ...",
+            "ok 3 - 2018-02 part 1 # SKIP not solved",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(tap(&results, &answers), expected);
+    }
+}
@@ -1,14 +1,25 @@
 use std::{collections::HashSet, io};
 
-use crate::template::{Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{Day, Puzzle, Year, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
 
 use super::{
     all_days,
+    answers::Answers,
+    fingerprint::Fingerprint,
+    report::{self, PartResult, Report},
     timings::{Timing, Timings},
 };
 
-pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -> Option<Timings> {
+pub fn run_multi(
+    year: Option<Year>,
+    days_to_run: &HashSet<Day>,
+    is_release: bool,
+    is_timed: bool,
+    reports: &[Report],
+) -> Option<Timings> {
     let mut timings: Vec<Timing> = Vec::with_capacity(days_to_run.len());
+    let mut results: Vec<PartResult> = vec![];
+    let answers = Answers::read_from_file(year);
 
     let mut need_space = false;
 
@@ -24,16 +35,35 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
             println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
             println!("------");
 
-            let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+            let puzzle = Puzzle::new(year, day);
+            let fingerprint = Fingerprint::of(puzzle);
+            let output = child_commands::run_solution(puzzle, is_timed, is_release).unwrap();
 
             if output.is_empty() {
                 println!("Not solved.");
             } else {
-                let val = child_commands::parse_exec_time(&output, day);
+                let mut val = child_commands::parse_exec_time(&output, day);
+                val.fingerprint = Some(fingerprint.clone());
                 timings.push(val);
+                results.extend(child_commands::parse_results(&output, puzzle));
+            }
+
+            let changes = answers.stale_changes(day, &fingerprint);
+            if !changes.is_empty() {
+                println!(
+                    "{ANSI_ITALIC}Recorded answers are stale: {} changed.{ANSI_RESET}",
+                    changes.join(" and ")
+                );
             }
         });
 
+    if !reports.is_empty() {
+        println!();
+        if let Err(e) = report::write(reports, &results, &answers) {
+            eprintln!("Failed to write report: {e}");
+        }
+    }
+
     if is_timed {
         let timings = Timings { data: timings };
         let total_millis = timings.total_millis();
@@ -60,15 +90,16 @@ impl From<std::io::Error> for Error {
 }
 
 #[must_use]
-pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
+pub fn get_path_for_bin(puzzle: Puzzle) -> String {
+    format!("./src/bin/{}.rs", puzzle.bin_name())
 }
 
 /// All solutions live in isolated binaries.
 /// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
 pub mod child_commands {
-    use super::{get_path_for_bin, Error};
-    use crate::template::Day;
+    use super::{get_path_for_bin, Error, PartResult};
+    use crate::template::runner::NO_SECOND_PART;
+    use crate::template::{Day, Puzzle, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
     use std::{
         io::{BufRead, BufReader},
         path::Path,
@@ -77,14 +108,18 @@ pub mod child_commands {
     };
 
     /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
+    pub fn run_solution(
+        puzzle: Puzzle,
+        is_timed: bool,
+        is_release: bool,
+    ) -> Result<Vec<String>, Error> {
         // skip command invocation for days that have not been scaffolded yet.
-        if !Path::new(&get_path_for_bin(day)).exists() {
+        if !Path::new(&get_path_for_bin(puzzle)).exists() {
             return Ok(vec![]);
         }
 
-        let day_padded = day.to_string();
-        let mut args = vec!["run", "--quiet", "--bin", &day_padded];
+        let bin_name = puzzle.bin_name();
+        let mut args = vec!["run", "--quiet", "--bin", &bin_name];
 
         if is_release {
             args.push("--release");
@@ -134,6 +169,8 @@ pub mod child_commands {
             part_1: None,
             part_2: None,
             total_nanos: 0_f64,
+            fingerprint: None,
+            single_part: output.iter().any(|l| l.contains(NO_SECOND_PART)),
         };
 
         output
@@ -164,12 +201,79 @@ pub mod child_commands {
         timings
     }
 
+    /// Parse the answer and duration of every part from the output of a solution.
+    pub fn parse_results(output: &[String], puzzle: Puzzle) -> Vec<PartResult> {
+        let mut results: Vec<PartResult> = vec![];
+        // lines of a multi-line answer follow the line of their part.
+        let mut multi_line: Option<Vec<&str>> = None;
+
+        for line in output {
+            // intermediate results are overwritten with a carriage return.
+            let line = line.rsplit('\r').next().unwrap_or_default();
+
+            let part = line
+                .strip_prefix("Part ")
+                .and_then(|l| l.split_once(": "))
+                .and_then(|(part, rest)| Some((part.parse::<u8>().ok()?, rest)));
+
+            let Some((part, rest)) = part else {
+                if let Some(lines) = multi_line.as_mut() {
+                    lines.push(line);
+                }
+                continue;
+            };
+
+            if let (Some(lines), Some(result)) = (multi_line.take(), results.last_mut()) {
+                result.answer = Some(lines.join("\n").trim_end().to_string());
+            }
+
+            let rest = rest
+                .replace(ANSI_BOLD, "")
+                .replace(ANSI_ITALIC, "")
+                .replace(ANSI_RESET, "");
+            let (answer, duration) = match rest.rfind(" (") {
+                Some(i) => (rest[..i].trim(), rest[i..].trim()),
+                None => (rest.trim(), ""),
+            };
+
+            let nanos = duration
+                .strip_prefix('(')
+                .and_then(|d| d.strip_suffix(')'))
+                .and_then(|d| parse_duration(d.split('@').next()?.trim()));
+
+            let answer = match answer {
+                // single-part days have no result for part two.
+                NO_SECOND_PART => continue,
+                "✖" => None,
+                "▼" => {
+                    multi_line = Some(vec![]);
+                    None
+                }
+                answer => Some(answer.to_string()),
+            };
+
+            results.push(PartResult {
+                puzzle,
+                part,
+                answer,
+                nanos,
+            });
+        }
+
+        if let (Some(lines), Some(result)) = (multi_line, results.last_mut()) {
+            result.answer = Some(lines.join("\n").trim_end().to_string());
+        }
+
+        results
+    }
+
     fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
         s.split(postfix).next()?.parse().ok()
     }
 
     fn parse_time(line: &str) -> Option<(&str, f64)> {
         // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
+        #[allow(clippy::double_ended_iterator_last)]
         let str_timing = line
             .split(" samples)")
             .next()?
@@ -179,14 +283,16 @@ pub mod child_commands {
             .next()?
             .trim();
 
-        let parsed_timing = match str_timing {
+        Some((str_timing, parse_duration(str_timing)?))
+    }
+
+    fn parse_duration(s: &str) -> Option<f64> {
+        match s {
             s if s.contains("ns") => s.split("ns").next()?.parse::<f64>().ok(),
             s if s.contains("µs") => parse_to_float(s, "µs").map(|x| x * 1000_f64),
             s if s.contains("ms") => parse_to_float(s, "ms").map(|x| x * 1_000_000_f64),
             s => parse_to_float(s, "s").map(|x| x * 1_000_000_000_f64),
-        }?;
-
-        Some((str_timing, parsed_timing))
+        }
     }
 
     /// copied from: https://github.com/rust-lang/rust/blob/1.64.0/library/std/src/macros.rs#L328-L333
@@ -205,9 +311,9 @@ pub mod child_commands {
 
     #[cfg(feature = "test_lib")]
     mod tests {
-        use super::parse_exec_time;
+        use super::{parse_exec_time, parse_results};
 
-        use crate::day;
+        use crate::{day, template::Puzzle};
 
         #[test]
         fn parses_execution_times() {
@@ -253,5 +359,54 @@ pub mod child_commands {
             assert_eq!(res.part_1.is_none(), true);
             assert_eq!(res.part_2.is_none(), true);
         }
+
+        #[test]
+        fn parses_single_part_days() {
+            let output = [
+                "Part 1: \x1b[1m42\x1b[0m (1.0ms @ 10 samples)".into(),
+                "Part 2: \x1b[3mno second part\x1b[0m".into(),
+                "".into(),
+            ];
+            let timing = parse_exec_time(&output, day!(25));
+            assert_eq!(timing.single_part, true);
+            assert_eq!(timing.part_2, None);
+
+            let res = parse_results(&output, day!(25).into());
+            assert_eq!(res.len(), 1);
+            assert_eq!(res[0].answer.as_deref(), Some("42"));
+        }
+
+        #[test]
+        fn parses_results() {
+            let puzzle = Puzzle::from(day!(1));
+            let res = parse_results(
+                &[
+                    "Part 1: \x1b[1m(1, 2)\x1b[0m\rPart 1: \x1b[1m(1, 2)\x1b[0m (2.5ms @ 10 samples)".into(),
+                    "Part 2: ▼ \rPart 2: ▼  (1.0µs)".into(),
+                    "#.#".into(),
+                    ".#.".into(),
+                    "".into(),
+                ],
+                puzzle,
+            );
+            assert_eq!(res.len(), 2);
+            assert_eq!(res[0].part, 1);
+            assert_eq!(res[0].answer.as_deref(), Some("(1, 2)"));
+            assert_approx_eq!(res[0].nanos.unwrap(), 2_500_000_f64);
+            assert_eq!(res[1].part, 2);
+            assert_eq!(res[1].answer.as_deref(), Some("#.#\n.#."));
+            assert_approx_eq!(res[1].nanos.unwrap(), 1000_f64);
+        }
+
+        #[test]
+        fn parses_missing_results() {
+            let res = parse_results(
+                &["Part 1: ✖\rPart 1: ✖             ".into()],
+                day!(1).into(),
+            );
+            assert_eq!(res.len(), 1);
+            assert_eq!(res[0].answer, None);
+            assert_eq!(res[0].nanos, None);
+        }
     }
 }
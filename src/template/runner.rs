@@ -1,15 +1,23 @@
 /// Encapsulates code that interacts with solution functions.
-use std::fmt::Display;
 use std::hint::black_box;
-use std::io::{stdout, Write};
+use std::io::{stdin, stdout, Write};
 use std::process::Output;
 use std::time::{Duration, Instant};
 use std::{cmp, env, process};
 
+use crate::template::answers::Answers;
+use crate::template::config::{config, SubmitPolicy};
+use crate::template::fingerprint::Fingerprint;
 use crate::template::ANSI_BOLD;
-use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{aoc_cli, Puzzle, ANSI_ITALIC, ANSI_RESET};
+use crate::Answer;
 
-pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+pub fn run_part<I: Clone, T: Into<Answer>>(
+    func: impl Fn(I) -> Option<T>,
+    input: I,
+    puzzle: Puzzle,
+    part: u8,
+) {
     let part_str = format!("Part {part}");
 
     let (result, duration, samples) =
@@ -18,18 +26,40 @@ pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, d
     print_result(&result, &part_str, &format_duration(&duration, samples));
 
     if let Some(result) = result {
-        submit_result(result, day, part);
+        if let Some((answer, Ok(output))) = submit_result(&result, puzzle, part) {
+            if aoc_cli::is_correct_answer(&output) {
+                record_answer(puzzle, part, answer);
+            }
+        }
+    }
+}
+
+/// Record a correct answer with the fingerprint of the input and source it was computed from.
+fn record_answer(puzzle: Puzzle, part: u8, answer: String) {
+    let mut answers = Answers::read_from_file(puzzle.year());
+    answers.record(puzzle.day(), part, answer, Fingerprint::of(puzzle));
+
+    match answers.store_file(puzzle.year()) {
+        Ok(()) => println!("Recorded answer for part {part}."),
+        Err(e) => eprintln!("Failed to record answer: {e}"),
     }
 }
 
+/// Printed in place of part two for days that only have a single part.
+pub const NO_SECOND_PART: &str = "no second part";
+
+pub fn print_no_second_part() {
+    println!("Part 2: {ANSI_ITALIC}{NO_SECOND_PART}{ANSI_RESET}");
+}
+
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
 ///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
-fn run_timed<I: Clone, T>(
-    func: impl Fn(I) -> T,
+fn run_timed<I: Clone, T: Into<Answer>>(
+    func: impl Fn(I) -> Option<T>,
     input: I,
-    hook: impl Fn(&T),
-) -> (T, Duration, u128) {
+    hook: impl Fn(&Option<Answer>),
+) -> (Option<Answer>, Duration, u128) {
     let timer = Instant::now();
     let result = {
         let input = input.clone();
@@ -41,6 +71,9 @@ fn run_timed<I: Clone, T>(
     };
     let base_time = timer.elapsed();
 
+    let result = result
+        .map(Into::into)
+        .filter(|answer| *answer != Answer::None);
     hook(&result);
 
     let run = if std::env::args().any(|x| x == "--time") {
@@ -94,12 +127,12 @@ fn format_duration(duration: &Duration, samples: u128) -> String {
     }
 }
 
-fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
+fn print_result(result: &Option<Answer>, part: &str, duration_str: &str) {
     let is_intermediate_result = duration_str.is_empty();
 
     match result {
         Some(result) => {
-            if result.to_string().contains('\n') {
+            if result.is_multi_line() {
                 let str = format!("{part}: ▼ {duration_str}");
                 if is_intermediate_result {
                     print!("{str}");
@@ -132,11 +165,14 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
 /// Parse the arguments passed to `solve` and try to submit one part of the solution if:
 ///  1. we are in `--release` mode.
 ///  2. aoc-cli is installed.
-fn submit_result<T: Display>(
-    result: T,
-    day: Day,
+///  3. the submit policy in `aoc.toml` allows it.
+///
+/// Returns the submitted answer and the result of the submission.
+fn submit_result(
+    result: &Answer,
+    puzzle: Puzzle,
     part: u8,
-) -> Option<Result<Output, aoc_cli::AocCommandError>> {
+) -> Option<(String, Result<Output, aoc_cli::AocCommandError>)> {
     let args: Vec<String> = env::args().collect();
 
     if !args.contains(&"--submit".into()) {
@@ -159,11 +195,36 @@ fn submit_result<T: Display>(
         return None;
     }
 
+    let Some(answer) = result.submit_string() else {
+        eprintln!("This answer can not be submitted automatically. Read it, then submit it on the website.");
+        return None;
+    };
+
+    match config().submit_policy {
+        SubmitPolicy::Allow => {}
+        SubmitPolicy::Never => {
+            eprintln!("Submitting is disabled by the submit policy in aoc.toml.");
+            return None;
+        }
+        SubmitPolicy::Confirm => {
+            print!("Submit \"{answer}\" for {puzzle}, part {part}? [y/N] ");
+            let _ = stdout().flush();
+            let mut confirmation = String::new();
+            if stdin().read_line(&mut confirmation).is_err()
+                || !confirmation.trim().eq_ignore_ascii_case("y")
+            {
+                println!("Not submitting.");
+                return None;
+            }
+        }
+    }
+
     if aoc_cli::check().is_err() {
         eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
         process::exit(1);
     }
 
     println!("Submitting result via aoc-cli...");
-    Some(aoc_cli::submit(day, part, &result.to_string()))
+    let output = aoc_cli::submit(puzzle, part, &answer);
+    Some((answer, output))
 }
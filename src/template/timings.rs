@@ -1,9 +1,11 @@
-use std::{collections::HashMap, fs, io::Error, str::FromStr};
+use std::{collections::HashMap, fs, io::Error, path::Path, str::FromStr};
 use tinyjson::JsonValue;
 
-use crate::template::Day;
-
-static TIMINGS_FILE_PATH: &str = "./data/timings.json";
+use crate::template::{
+    config::config,
+    fingerprint::{stale_changes, Fingerprint},
+    Day, Year,
+};
 
 /// Represents benchmark times for a single day.
 #[derive(Clone, Debug)]
@@ -12,6 +14,10 @@ pub struct Timing {
     pub part_1: Option<String>,
     pub part_2: Option<String>,
     pub total_nanos: f64,
+    /// The input and source the timing was measured with.
+    pub fingerprint: Option<Fingerprint>,
+    /// Set for days that do not have a second part.
+    pub single_part: bool,
 }
 
 /// Represents benchmark times for a set of days.
@@ -22,16 +28,20 @@ pub struct Timings {
 }
 
 impl Timings {
-    /// Dehydrate timings to a JSON file.
-    pub fn store_file(&self) -> Result<(), Error> {
+    /// Dehydrate timings of a year to a JSON file.
+    pub fn store_file(&self, year: Option<Year>) -> Result<(), Error> {
         let json = JsonValue::from(self.clone());
-        let mut file = fs::File::create(TIMINGS_FILE_PATH)?;
+        let path = get_path_for_year(year);
+        if let Some(parent) = Path::new(&path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = fs::File::create(path)?;
         json.format_to(&mut file)
     }
 
-    /// Rehydrate timings from a JSON file. If not present, returns empty timings.
-    pub fn read_from_file() -> Self {
-        let s = fs::read_to_string(TIMINGS_FILE_PATH)
+    /// Rehydrate timings of a year from a JSON file. If not present, returns empty timings.
+    pub fn read_from_file(year: Option<Year>) -> Self {
+        let s = fs::read_to_string(get_path_for_year(year))
             .map_err(|x| x.to_string())
             .and_then(Timings::try_from);
 
@@ -58,7 +68,7 @@ impl Timings {
             }
         }
 
-        data.sort_unstable_by(|a, b| a.day.cmp(&b.day));
+        data.sort_unstable_by_key(|a| a.day);
         Timings { data }
     }
 
@@ -70,10 +80,23 @@ impl Timings {
     pub fn is_day_complete(&self, day: Day) -> bool {
         self.data
             .iter()
-            .any(|t| t.day == day && t.part_1.is_some() && t.part_2.is_some())
+            .any(|t| t.day == day && t.part_1.is_some() && (t.part_2.is_some() || t.single_part))
+    }
+
+    /// Returns which files changed since the timing of a day was stored.
+    pub fn stale_changes(&self, day: Day, current: &Fingerprint) -> Vec<&'static str> {
+        self.data
+            .iter()
+            .find(|t| t.day == day)
+            .map(|t| stale_changes(t.fingerprint.as_ref(), current))
+            .unwrap_or_default()
     }
 }
 
+fn get_path_for_year(year: Option<Year>) -> String {
+    config().scoped_path(&config().timings_path, year)
+}
+
 /* -------------------------------------------------------------------------- */
 
 impl From<Timings> for JsonValue {
@@ -121,6 +144,14 @@ impl From<&Timing> for JsonValue {
         map.insert("day".into(), JsonValue::String(value.day.to_string()));
         map.insert("total_nanos".into(), JsonValue::Number(value.total_nanos));
 
+        if let Some(fingerprint) = &value.fingerprint {
+            fingerprint.insert_into(&mut map);
+        }
+
+        if value.single_part {
+            map.insert("single_part".into(), JsonValue::Boolean(true));
+        }
+
         let part_1 = value.part_1.clone().map(JsonValue::String);
         let part_2 = value.part_2.clone().map(JsonValue::String);
 
@@ -178,6 +209,11 @@ impl TryFrom<&JsonValue> for Timing {
             part_1: part_1.cloned(),
             part_2: part_2.cloned(),
             total_nanos,
+            fingerprint: Fingerprint::from_json(json)?,
+            single_part: json
+                .get("single_part")
+                .and_then(|v| v.get::<bool>().copied())
+                .unwrap_or(false),
         })
     }
 }
@@ -198,25 +234,34 @@ mod tests {
                     part_1: Some("10ms".into()),
                     part_2: Some("20ms".into()),
                     total_nanos: 3e+10,
+                    fingerprint: None,
+                    single_part: false,
                 },
                 Timing {
                     day: day!(2),
                     part_1: Some("30ms".into()),
                     part_2: Some("40ms".into()),
                     total_nanos: 7e+10,
+                    fingerprint: None,
+                    single_part: false,
                 },
                 Timing {
                     day: day!(4),
                     part_1: Some("40ms".into()),
                     part_2: None,
                     total_nanos: 4e+10,
+                    fingerprint: None,
+                    single_part: false,
                 },
             ],
         }
     }
 
     mod deserialization {
-        use crate::{day, template::timings::Timings};
+        use crate::{
+            day,
+            template::{fingerprint::Fingerprint, timings::Timings},
+        };
 
         #[test]
         fn handles_json_timings() {
@@ -230,6 +275,23 @@ mod tests {
             assert_eq!(timing.total_nanos, 1_000_000_000_f64);
         }
 
+        #[test]
+        fn handles_fingerprints() {
+            let json = r#"{ "data": [{ "day": "01", "part_1": "1ms", "part_2": "2ms", "total_nanos": 3000000, "input_hash": "a", "source_hash": "b" }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let fingerprint = timings.data[0].fingerprint.clone().unwrap();
+            assert_eq!(fingerprint.input, Some("a".into()));
+            assert_eq!(fingerprint.source, Some("b".into()));
+            assert!(timings.stale_changes(day!(1), &fingerprint).is_empty());
+
+            let changed = Fingerprint {
+                source: Some("c".into()),
+                ..fingerprint
+            };
+            assert_eq!(timings.stale_changes(day!(1), &changed), vec!["source"]);
+            assert!(timings.stale_changes(day!(2), &changed).is_empty());
+        }
+
         #[test]
         fn handles_empty_timings() {
             let json = r#"{ "data": [] }"#.to_string();
@@ -289,10 +351,12 @@ mod tests {
                     part_1: Some("1ms".into()),
                     part_2: Some("2ms".into()),
                     total_nanos: 3_000_000_000_f64,
+                    fingerprint: None,
+                    single_part: false,
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), true);
+            assert_eq!(timings.is_day_complete(day!(1)), true);
         }
 
         #[test]
@@ -303,10 +367,28 @@ mod tests {
                     part_1: Some("1ms".into()),
                     part_2: None,
                     total_nanos: 1_000_000_000_f64,
+                    fingerprint: None,
+                    single_part: false,
+                }],
+            };
+
+            assert_eq!(timings.is_day_complete(day!(1)), false);
+        }
+
+        #[test]
+        fn handles_single_part_days() {
+            let timings = Timings {
+                data: vec![Timing {
+                    day: day!(25),
+                    part_1: Some("1ms".into()),
+                    part_2: None,
+                    total_nanos: 1_000_000_000_f64,
+                    fingerprint: None,
+                    single_part: true,
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(25)), true);
         }
 
         #[test]
@@ -317,10 +399,12 @@ mod tests {
                     part_1: None,
                     part_2: None,
                     total_nanos: 0.0,
+                    fingerprint: None,
+                    single_part: false,
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
     }
 
@@ -341,6 +425,8 @@ mod tests {
                     part_1: None,
                     part_2: None,
                     total_nanos: 0_f64,
+                    fingerprint: None,
+                    single_part: false,
                 }],
             };
             let merged = timings.merge(&other);
@@ -361,6 +447,8 @@ mod tests {
                     part_1: None,
                     part_2: None,
                     total_nanos: 0_f64,
+                    fingerprint: None,
+                    single_part: false,
                 }],
             };
             let merged = timings.merge(&other);